@@ -1,17 +1,21 @@
 use eframe::egui;
+use fuzzy_matcher::clangd::ClangdMatcher;
 use fuzzy_matcher::skim::SkimMatcherV2;
 use fuzzy_matcher::FuzzyMatcher;
 use rayon::prelude::*;
-use reqwest;
 use serde::Deserialize;
 use std::fs::{self, File, OpenOptions};
 use std::io::Write;
 use std::path::PathBuf;
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
 use std::process::Command;
-use std::time::{Duration, Instant};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 use std::sync::{Arc, Mutex};
-use dirs;
 use std::thread;
+use tracing::{debug, error, info, warn};
+use unicode_normalization::char::is_combining_mark;
+use unicode_normalization::UnicodeNormalization;
 
 #[derive(Clone, Debug)]
 struct HotkeyConfig {
@@ -74,1431 +78,7703 @@ impl HotkeyConfig {
     }
 }
 
-#[derive(Clone, Copy, PartialEq)]
-enum AppMode {
-    Launcher,
-    Settings,
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum RunMode {
+    Background,
+    Terminal,
+    LoginShell,
 }
 
-struct Theme {
-    background: String,
-    text_color: String,
-    selection_bg: String,
-    selection_text: String,
-    border_color: String,
-    font_size: f32,
-    border_radius: f32,
-    font_family: String,
-    highlight_color: String,
+impl RunMode {
+    const ALL: [RunMode; 3] = [RunMode::Background, RunMode::Terminal, RunMode::LoginShell];
+    
+    fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_lowercase().as_str() {
+            "background" => Some(RunMode::Background),
+            "terminal" => Some(RunMode::Terminal),
+            "shell" | "login_shell" => Some(RunMode::LoginShell),
+            _ => None,
+        }
+    }
+    
+    fn as_str(&self) -> &'static str {
+        match self {
+            RunMode::Background => "background",
+            RunMode::Terminal => "terminal",
+            RunMode::LoginShell => "shell",
+        }
+    }
+    
+    fn label(&self) -> &'static str {
+        match self {
+            RunMode::Background => "Run in background",
+            RunMode::Terminal => "Run in terminal",
+            RunMode::LoginShell => "Run in login shell",
+        }
+    }
 }
 
-impl Default for Theme {
+#[derive(Clone, Debug)]
+struct CommandConfig {
+    run_mode: RunMode,
+    terminal: String,
+    terminal_args: String,
+    history_size: usize,
+    output_timeout_secs: u64,
+}
+
+impl Default for CommandConfig {
     fn default() -> Self {
         Self {
-            background: "#2d2d30".to_string(),
-            text_color: "#ffffff".to_string(),
-            selection_bg: "#0078d4".to_string(),
-            selection_text: "#ffffff".to_string(),
-            border_color: "#3e3e42".to_string(),
-            font_size: 16.0,
-            border_radius: 2.0,
-            font_family: "Segoe UI".to_string(),
-            highlight_color: "#0078d4".to_string(),
+            run_mode: RunMode::Background,
+            terminal: default_terminal().to_string(),
+            terminal_args: String::new(),
+            history_size: 50,
+            output_timeout_secs: 5,
         }
     }
 }
 
-impl Theme {
-    fn load_from_config() -> Self {
-        let config_dir = get_config_dir();
-        let theme_path = config_dir.join("theme.conf");
-        
-        if !theme_path.exists() {
-            create_default_theme(&theme_path);
-            return Self::default();
-        }
-        
-        let mut theme = Self::default();
+impl CommandConfig {
+    fn load() -> Self {
+        let config_path = get_config_dir().join("commands.conf");
+        let mut config = Self::default();
         
-        if let Ok(content) = fs::read_to_string(&theme_path) {
+        if let Ok(content) = fs::read_to_string(&config_path) {
             for line in content.lines() {
                 let parts: Vec<&str> = line.splitn(2, '=').collect();
-                if parts.len() == 2 {
-                    let key = parts[0].trim();
-                    let value = parts[1].trim();
-                    
-                    match key {
-                        "background" => theme.background = value.to_string(),
-                        "text_color" => theme.text_color = value.to_string(),
-                        "selection_bg" => theme.selection_bg = value.to_string(),
-                        "selection_text" => theme.selection_text = value.to_string(),
-                        "border_color" => theme.border_color = value.to_string(),
-                        "highlight_color" => theme.highlight_color = value.to_string(),
-                        "font_size" => {
-                            if let Ok(size) = value.parse() {
-                                theme.font_size = size;
-                            }
+                if line.starts_with('#') || parts.len() != 2 {
+                    continue;
+                }
+                let value = parts[1].trim();
+                match parts[0].trim() {
+                    "run_mode" => {
+                        if let Some(mode) = RunMode::parse(value) {
+                            config.run_mode = mode;
                         }
-                        "border_radius" => {
-                            if let Ok(radius) = value.parse() {
-                                theme.border_radius = radius;
-                            }
+                    }
+                    "terminal" if !value.is_empty() => config.terminal = value.to_string(),
+                    "terminal_args" => config.terminal_args = value.to_string(),
+                    "history_size" => {
+                        if let Ok(size) = value.parse() {
+                            config.history_size = size;
+                        }
+                    }
+                    "output_timeout_secs" => {
+                        if let Ok(secs) = value.parse() {
+                            config.output_timeout_secs = secs;
                         }
-                        "font_family" => theme.font_family = value.to_string(),
-                        _ => {}
                     }
+                    _ => {}
                 }
             }
         }
         
-        theme
+        config
     }
     
-    fn hex_to_rgb(&self, hex: &str) -> [f32; 3] {
-        let hex = hex.trim_start_matches('#');
-        if hex.len() == 6 {
-            if let (Ok(r), Ok(g), Ok(b)) = (
-                u8::from_str_radix(&hex[0..2], 16),
-                u8::from_str_radix(&hex[2..4], 16),
-                u8::from_str_radix(&hex[4..6], 16),
-            ) {
-                return [r as f32 / 255.0, g as f32 / 255.0, b as f32 / 255.0];
+    fn save(&self) {
+        let config_dir = get_config_dir();
+        let _ = fs::create_dir_all(&config_dir);
+        
+        let content = format!(
+            "# Flint Launcher Command Mode Configuration\n\
+             # run_mode: background, terminal or shell ($SHELL as a login shell)\n\
+             # terminal: emulator for terminal commands and Terminal=true apps, or auto to detect one\n\
+             # terminal_args: argument template, {{}} is replaced by the command (empty = emulator default)\n\
+             # output_timeout_secs: time limit for inline (>) commands\n\n\
+             run_mode={}\n\
+             terminal={}\n\
+             terminal_args={}\n\
+             history_size={}\n\
+             output_timeout_secs={}\n",
+            self.run_mode.as_str(),
+            self.terminal,
+            self.terminal_args,
+            self.history_size,
+            self.output_timeout_secs
+        );
+        
+        let _ = fs::write(config_dir.join("commands.conf"), content);
+    }
+    
+    fn resolved_terminal(&self) -> String {
+        if self.terminal == "auto" {
+            detect_terminal().unwrap_or_else(|| KNOWN_TERMINALS[0].0.to_string())
+        } else {
+            self.terminal.clone()
+        }
+    }
+    
+    fn terminal_command(&self, command: Vec<String>) -> Vec<String> {
+        let terminal = self.resolved_terminal();
+        let template = if self.terminal_args.trim().is_empty() {
+            terminal_args_template(&terminal).to_string()
+        } else {
+            self.terminal_args.clone()
+        };
+        
+        let mut argv = vec![terminal];
+        let mut inserted = false;
+        for token in template.split_whitespace() {
+            if token == "{}" {
+                argv.extend(command.iter().cloned());
+                inserted = true;
+            } else {
+                argv.push(token.to_string());
             }
         }
-        [0.0, 0.0, 0.0]
+        if !inserted {
+            argv.extend(command);
+        }
+        argv
     }
 }
 
-#[derive(Clone)]
-enum ResultType {
-    App(AppEntry),
-    Calculator(String),
-    Command(String),
-    WebSearch(String),
-    Url(String),
-    File(PathBuf),
-    Emoji(String, String),
-    Currency(String, String, f64),
+struct CommandHistory {
+    entries: Vec<String>,
 }
 
-#[derive(Clone)]
-struct AppEntry {
-    name: String,
-    desktop_id: String,
-    exec_command: String,
-    match_indices: Vec<usize>,
+impl CommandHistory {
+    fn load() -> Self {
+        let entries = fs::read_to_string(get_config_dir().join("command_history"))
+            .map(|content| {
+                content.lines()
+                    .filter(|line| !line.trim().is_empty())
+                    .map(|line| line.to_string())
+                    .collect()
+            })
+            .unwrap_or_default();
+        
+        Self { entries }
+    }
+    
+    fn record(&mut self, cmd: &str, max_entries: usize) {
+        let cmd = cmd.trim();
+        if cmd.is_empty() {
+            return;
+        }
+        
+        self.entries.retain(|entry| entry != cmd);
+        self.entries.insert(0, cmd.to_string());
+        self.entries.truncate(max_entries);
+        
+        let config_dir = get_config_dir();
+        let _ = fs::create_dir_all(&config_dir);
+        let _ = fs::write(config_dir.join("command_history"), self.entries.join("\n"));
+    }
+    
+    fn matching(&self, prefix: &str) -> impl Iterator<Item = &String> {
+        let prefix = prefix.to_string();
+        self.entries.iter().filter(move |entry| entry.starts_with(&prefix) && **entry != prefix)
+    }
 }
 
-struct AnimationState {
-    progress: f32,
-    start_time: Instant,
-    duration: Duration,
-    animation_type: AnimationType,
+const LEARNING_HALF_LIFE_DAYS: f64 = 14.0;
+const LEARNING_MAX_BOOST: f64 = 0.5;
+const LEARNING_MIN_COUNT: f64 = 0.05;
+const LEARNING_MAX_ENTRIES: usize = 500;
+
+struct LearnedSelection {
+    query: String,
+    key: String,
+    count: f64,
+    last_used: u64,
 }
 
-impl AnimationState {
-    fn new(duration: Duration, animation_type: AnimationType) -> Self {
-        Self {
-            progress: 0.0,
-            start_time: Instant::now(),
-            duration,
-            animation_type,
+impl LearnedSelection {
+    fn decayed_count(&self, now: u64) -> f64 {
+        let age_days = now.saturating_sub(self.last_used) as f64 / 86400.0;
+        self.count * 0.5f64.powf(age_days / LEARNING_HALF_LIFE_DAYS)
+    }
+}
+
+struct SelectionMemory {
+    entries: Vec<LearnedSelection>,
+}
+
+impl SelectionMemory {
+    fn load() -> Self {
+        let now = unix_now();
+        let entries = fs::read_to_string(get_config_dir().join("learned_selections"))
+            .map(|content| {
+                content.lines()
+                    .filter_map(|line| {
+                        let mut fields = line.split('\t');
+                        let query = fields.next()?.to_string();
+                        let key = fields.next()?.to_string();
+                        let count = fields.next()?.parse().ok()?;
+                        let last_used = fields.next()?.parse().ok()?;
+                        Some(LearnedSelection { query, key, count, last_used })
+                    })
+                    .filter(|entry| entry.decayed_count(now) >= LEARNING_MIN_COUNT)
+                    .collect()
+            })
+            .unwrap_or_default();
+        
+        Self { entries }
+    }
+    
+    fn save(&self) {
+        let content: Vec<String> = self.entries.iter()
+            .map(|entry| format!("{}\t{}\t{:.4}\t{}", entry.query, entry.key, entry.count, entry.last_used))
+            .collect();
+        
+        let config_dir = get_config_dir();
+        let _ = fs::create_dir_all(&config_dir);
+        let _ = fs::write(config_dir.join("learned_selections"), content.join("\n"));
+    }
+    
+    fn record(&mut self, query: &str, key: String) {
+        let query = fold_text(query.trim(), false);
+        if query.is_empty() || query.contains(['\t', '\n']) || key.contains(['\t', '\n']) {
+            return;
         }
+        
+        let now = unix_now();
+        let count = match self.entries.iter().position(|entry| entry.query == query && entry.key == key) {
+            Some(index) => self.entries.remove(index).decayed_count(now) + 1.0,
+            None => 1.0,
+        };
+        self.entries.insert(0, LearnedSelection { query, key, count, last_used: now });
+        self.entries.retain(|entry| entry.decayed_count(now) >= LEARNING_MIN_COUNT);
+        self.entries.truncate(LEARNING_MAX_ENTRIES);
+        self.save();
     }
     
-    fn update(&mut self) -> bool {
-        let elapsed = self.start_time.elapsed();
-        self.progress = (elapsed.as_millis() as f32 / self.duration.as_millis() as f32).min(1.0);
-        self.progress < 1.0
+    fn boosts(&self, query: &str) -> std::collections::HashMap<&str, f64> {
+        let query = fold_text(query.trim(), false);
+        let mut boosts: std::collections::HashMap<&str, f64> = std::collections::HashMap::new();
+        if query.is_empty() {
+            return boosts;
+        }
+        
+        let now = unix_now();
+        for entry in &self.entries {
+            if !entry.query.starts_with(&query) {
+                continue;
+            }
+            let closeness = query.chars().count() as f64 / entry.query.chars().count() as f64;
+            let strength = 1.0 - 0.5f64.powf(entry.decayed_count(now));
+            let boost = boosts.entry(entry.key.as_str()).or_insert(0.0);
+            *boost = boost.max(LEARNING_MAX_BOOST * strength * closeness);
+        }
+        boosts
     }
     
-    fn ease_out(&self) -> f32 {
-        1.0 - (1.0 - self.progress).powf(2.0)
+    fn rank_learned(&self, query: &str, results: &mut Vec<ResultType>) {
+        let boosts = self.boosts(query);
+        if boosts.is_empty() {
+            return;
+        }
+        
+        let mut ranked: Vec<(f64, ResultType)> = results.drain(..)
+            .map(|result| {
+                let boost = result.learning_key()
+                    .and_then(|key| boosts.get(key.as_str()).copied())
+                    .unwrap_or_default();
+                (boost, result)
+            })
+            .collect();
+        ranked.sort_by(|a, b| b.0.total_cmp(&a.0));
+        results.extend(ranked.into_iter().map(|(_, result)| result));
+    }
+    
+    fn learned_apps(&self, query: &str) -> std::collections::HashSet<&str> {
+        self.boosts(query)
+            .into_keys()
+            .filter_map(|key| {
+                key.strip_prefix("app:")
+                    .or_else(|| key.strip_prefix("action:").and_then(|key| key.rsplit_once(':')).map(|(id, _)| id))
+            })
+            .collect()
+    }
+    
+    fn reset(&mut self) {
+        self.entries.clear();
+        let _ = fs::remove_file(get_config_dir().join("learned_selections"));
     }
 }
 
-#[derive(Clone, Copy)]
-enum AnimationType {
-    FadeIn,
-    SlideDown,
+fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_secs())
+        .unwrap_or_default()
 }
 
-struct FlintApp {
-    query: String,
-    results: Vec<ResultType>,
-    items: Vec<AppEntry>,
-    selected: usize,
-    should_close: bool,
-    has_focused: bool,
-    theme: Theme,
-    _lock_file: File,
-    window_animation: AnimationState,
-    result_animations: Vec<AnimationState>,
-    runtime: tokio::runtime::Runtime,
-    app_mode: AppMode,
-    hotkey_config: Arc<Mutex<HotkeyConfig>>,
-    temp_launcher_key: String,
-    temp_settings_key: String,
-    temp_enabled: bool,
-    status_message: String,
-    status_color: egui::Color32,
-    message_time: Instant,
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum MatcherBackend {
+    Skim,
+    Clangd,
 }
 
-fn start_tray_thread() {
-    thread::spawn(move || {
-        #[cfg(target_os = "windows")]
-        {
-            use tray_item::TrayItem;
-            
-            if let Ok(mut tray) = TrayItem::new("Flint Launcher", "") {
-                let _ = tray.add_label("Flint Launcher");
-                let _ = tray.inner_mut().add_separator();
-                
-                let _ = tray.add_menu_item("Show Launcher", || {
-                    if let Ok(exe_path) = std::env::current_exe() {
-                        let _ = Command::new(exe_path).spawn();
-                    }
-                });
-                
-                let _ = tray.add_menu_item("Settings", || {
-                    if let Ok(exe_path) = std::env::current_exe() {
-                        let _ = Command::new(exe_path).arg("settings").spawn();
-                    }
-                });
-                
-                let _ = tray.inner_mut().add_separator();
-                
-                let _ = tray.add_menu_item("Open Config Folder", || {
-                    let config_dir = get_config_dir();
-                    let _ = open_file(&config_dir);
-                });
-                
-                let _ = tray.inner_mut().add_separator();
-                
-                let _ = tray.add_menu_item("Show Theme File", || {
-                    let theme_path = get_config_dir().join("theme.conf");
-                    let _ = open_file(&theme_path);
-                });
-                
-                let _ = tray.inner_mut().add_separator();
-                
-                let _ = tray.add_menu_item("Exit", || {
-                    std::process::exit(0);
-                });
-            }
-        }
-        
-        loop {
-            thread::sleep(Duration::from_secs(1));
+impl MatcherBackend {
+    const ALL: [MatcherBackend; 2] = [MatcherBackend::Skim, MatcherBackend::Clangd];
+    
+    fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_lowercase().as_str() {
+            "skim" => Some(MatcherBackend::Skim),
+            "clangd" => Some(MatcherBackend::Clangd),
+            _ => None,
         }
-    });
+    }
+    
+    fn as_str(&self) -> &'static str {
+        match self {
+            MatcherBackend::Skim => "skim",
+            MatcherBackend::Clangd => "clangd",
+        }
+    }
+    
+    fn label(&self) -> &'static str {
+        match self {
+            MatcherBackend::Skim => "Skim (fzf-style)",
+            MatcherBackend::Clangd => "Clangd (stricter)",
+        }
+    }
+    
+    fn matcher(&self) -> Box<dyn FuzzyMatcher> {
+        match self {
+            MatcherBackend::Skim => Box::new(SkimMatcherV2::default()),
+            MatcherBackend::Clangd => Box::new(ClangdMatcher::default()),
+        }
+    }
 }
 
-impl FlintApp {
-    fn new() -> Result<Self, String> {
-        let lock_file = acquire_lock()?;
-        let items = scan_apps();
-        let runtime = tokio::runtime::Runtime::new()
-            .map_err(|e| format!("Failed to create async runtime: {}", e))?;
+#[derive(Clone, Debug)]
+struct SearchConfig {
+    backend: MatcherBackend,
+    name_weight: i64,
+    acronym_weight: i64,
+    generic_name_weight: i64,
+    keywords_weight: i64,
+    categories_weight: i64,
+    comment_weight: i64,
+    exec_weight: i64,
+    transliterate: bool,
+    providers: Vec<(String, ProviderSettings)>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct ProviderSettings {
+    weight: i64,
+    max_results: usize,
+}
+
+const BLENDED_PROVIDERS: [(&str, i64, usize); 8] = [
+    ("calculator", 100, 1),
+    ("currency", 100, 1),
+    ("url", 100, 1),
+    ("apps", 100, 8),
+    ("quicklinks", 100, 3),
+    ("games", 100, 3),
+    ("suggestions", 100, 3),
+    ("web", 100, 1),
+];
+
+impl Default for SearchConfig {
+    fn default() -> Self {
+        Self {
+            backend: MatcherBackend::Skim,
+            name_weight: 100,
+            acronym_weight: 100,
+            generic_name_weight: 70,
+            keywords_weight: 60,
+            categories_weight: 30,
+            comment_weight: 20,
+            exec_weight: 80,
+            transliterate: true,
+            providers: BLENDED_PROVIDERS.iter()
+                .map(|(name, weight, max_results)| (name.to_string(), ProviderSettings { weight: *weight, max_results: *max_results }))
+                .collect(),
+        }
+    }
+}
+
+impl SearchConfig {
+    fn load() -> Self {
+        let config_path = get_config_dir().join("search.conf");
+        let mut config = Self::default();
         
-        Ok(Self {
-            query: String::new(),
-            results: Vec::new(),
-            items,
-            selected: 0,
-            should_close: false,
-            has_focused: false,
-            theme: Theme::load_from_config(),
-            _lock_file: lock_file,
-            window_animation: AnimationState::new(Duration::from_millis(300), AnimationType::FadeIn),
-            result_animations: Vec::new(),
-            runtime,
-            app_mode: AppMode::Launcher,
-            hotkey_config: Arc::new(Mutex::new(HotkeyConfig::load())),
-            temp_launcher_key: String::new(),
-            temp_settings_key: String::new(),
-            temp_enabled: false,
-            status_message: String::new(),
-            status_color: egui::Color32::GREEN,
-            message_time: Instant::now(),
-        })
+        if let Ok(content) = fs::read_to_string(&config_path) {
+            for line in content.lines() {
+                let parts: Vec<&str> = line.splitn(2, '=').collect();
+                if line.starts_with('#') || parts.len() != 2 {
+                    continue;
+                }
+                let value = parts[1].trim();
+                match parts[0].trim() {
+                    "backend" => {
+                        if let Some(backend) = MatcherBackend::parse(value) {
+                            config.backend = backend;
+                        }
+                    }
+                    "transliterate" => config.transliterate = value == "true",
+                    key if key.starts_with("provider.") => {
+                        let name = &key["provider.".len()..];
+                        let (weight, max_results) = value.split_once(',').unwrap_or((value, ""));
+                        if let Some((_, settings)) = config.providers.iter_mut().find(|(provider, _)| provider == name) {
+                            if let Ok(weight) = weight.trim().parse::<i64>() {
+                                settings.weight = weight.max(0);
+                            }
+                            if let Ok(max_results) = max_results.trim().parse() {
+                                settings.max_results = max_results;
+                            }
+                        }
+                    }
+                    key => {
+                        let field = key.strip_suffix("_weight").unwrap_or(key);
+                        if let (Some((_, weight)), Ok(value)) = (
+                            config.weights_mut().into_iter().find(|(name, _)| *name == field),
+                            value.parse::<i64>(),
+                        ) {
+                            *weight = value.max(0);
+                        }
+                    }
+                }
+            }
+        }
+        
+        config
     }
     
-    fn update_result_animations(&mut self) {
-        if self.result_animations.len() != self.results.len() {
-            self.result_animations = self.results.iter()
-                .enumerate()
-                .map(|(i, _)| {
-                    let delay = Duration::from_millis((i * 40) as u64).min(Duration::from_millis(200));
-                    let mut anim = AnimationState::new(Duration::from_millis(250), AnimationType::SlideDown);
-                    anim.start_time += delay;
-                    anim
-                })
-                .collect();
-        }
+    fn save(&self) {
+        let config_dir = get_config_dir();
+        let _ = fs::create_dir_all(&config_dir);
         
-        for anim in &mut self.result_animations {
-            anim.update();
-        }
+        let content = format!(
+            "# Flint Launcher Search Configuration\n\
+             # backend: skim or clangd\n\
+             # *_weight: how much a match in each app field counts, in percent (0 disables the field)\n\
+             # transliterate: also match non-Latin names by their Latin spelling (pinyin, romaji, ...)\n\
+             # provider.NAME=weight,max: how strongly each provider ranks in mixed results (percent, 0 disables it)\n\
+             # and how many results it may contribute\n\n\
+             backend={}\n\
+             transliterate={}\n\
+             name_weight={}\n\
+             acronym_weight={}\n\
+             generic_name_weight={}\n\
+             keywords_weight={}\n\
+             categories_weight={}\n\
+             comment_weight={}\n\
+             exec_weight={}\n",
+            self.backend.as_str(),
+            self.transliterate,
+            self.name_weight,
+            self.acronym_weight,
+            self.generic_name_weight,
+            self.keywords_weight,
+            self.categories_weight,
+            self.comment_weight,
+            self.exec_weight
+        );
+        
+        let providers: String = self.providers.iter()
+            .map(|(name, settings)| format!("provider.{}={},{}\n", name, settings.weight, settings.max_results))
+            .collect();
+        
+        let _ = fs::write(config_dir.join("search.conf"), content + &providers);
     }
     
-    fn get_result_offset(&self, index: usize) -> f32 {
-        self.result_animations.get(index)
-            .map(|anim| {
-                match anim.animation_type {
-                    AnimationType::SlideDown => (1.0 - anim.ease_out()) * -30.0,
-                    _ => 0.0,
-                }
-            })
-            .unwrap_or(0.0)
+    fn provider(&self, name: &str) -> ProviderSettings {
+        self.providers.iter()
+            .find(|(provider, _)| provider == name)
+            .map(|(_, settings)| *settings)
+            .unwrap_or(ProviderSettings { weight: 100, max_results: 8 })
     }
     
-    fn get_result_alpha(&self, index: usize) -> f32 {
-        self.result_animations.get(index)
-            .map(|anim| anim.ease_out())
-            .unwrap_or(1.0)
+    fn provider_enabled(&self, name: &str) -> bool {
+        let settings = self.provider(name);
+        settings.weight > 0 && settings.max_results > 0
+    }
+    
+    fn weights_mut(&mut self) -> [(&'static str, &mut i64); 7] {
+        [
+            ("name", &mut self.name_weight),
+            ("acronym", &mut self.acronym_weight),
+            ("generic_name", &mut self.generic_name_weight),
+            ("keywords", &mut self.keywords_weight),
+            ("categories", &mut self.categories_weight),
+            ("comment", &mut self.comment_weight),
+            ("exec", &mut self.exec_weight),
+        ]
     }
 }
 
-impl eframe::App for FlintApp {
-    fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
-        if self.should_close {
-            ctx.send_viewport_cmd(egui::ViewportCommand::Close);
-            return;
-        }
-        
-        match self.app_mode {
-            AppMode::Settings => self.render_settings(ctx),
-            AppMode::Launcher => self.render_launcher(ctx),
+#[derive(Clone, Debug)]
+struct SnippetConfig {
+    auto_paste: bool,
+    paste_delay_ms: u64,
+    date_format: String,
+    time_format: String,
+}
+
+impl Default for SnippetConfig {
+    fn default() -> Self {
+        Self {
+            auto_paste: false,
+            paste_delay_ms: 300,
+            date_format: "%Y-%m-%d".to_string(),
+            time_format: "%H:%M".to_string(),
         }
     }
 }
 
-impl FlintApp {
-    fn render_launcher(&mut self, ctx: &egui::Context) {
-        let _window_animating = self.window_animation.update();
-        self.update_result_animations();
+impl SnippetConfig {
+    fn load() -> Self {
+        let config_path = get_config_dir().join("snippets.conf");
+        let mut config = Self::default();
         
-        let still_animating = self.window_animation.progress < 1.0 || self.result_animations.iter().any(|a| a.progress < 1.0);
+        if let Ok(content) = fs::read_to_string(&config_path) {
+            for line in content.lines() {
+                let parts: Vec<&str> = line.splitn(2, '=').collect();
+                if line.starts_with('#') || parts.len() != 2 {
+                    continue;
+                }
+                let value = parts[1].trim();
+                match parts[0].trim() {
+                    "auto_paste" => config.auto_paste = value == "true",
+                    "paste_delay_ms" => {
+                        if let Ok(delay) = value.parse() {
+                            config.paste_delay_ms = delay;
+                        }
+                    }
+                    "date_format" => config.date_format = value.to_string(),
+                    "time_format" => config.time_format = value.to_string(),
+                    _ => {}
+                }
+            }
+        }
         
-        if still_animating {
-            ctx.request_repaint();
+        config
+    }
+    
+    fn save(&self) {
+        let config_dir = get_config_dir();
+        let _ = fs::create_dir_all(&config_dir);
+        
+        let content = format!(
+            "# Flint Launcher Snippet Configuration\n\
+             # Snippets live in the snippets folder next to this file, one file per snippet.\n\
+             # Placeholders: {{date}} {{time}} {{clipboard}} {{uuid}} {{input:Name}}\n\n\
+             auto_paste={}\n\
+             paste_delay_ms={}\n\
+             date_format={}\n\
+             time_format={}\n",
+            self.auto_paste,
+            self.paste_delay_ms,
+            self.date_format,
+            self.time_format
+        );
+        
+        let _ = fs::write(config_dir.join("snippets.conf"), content);
+    }
+}
+
+#[derive(Clone)]
+struct Snippet {
+    name: String,
+    body: String,
+}
+
+struct SnippetForm {
+    snippet: Snippet,
+    fields: Vec<(String, String)>,
+    focus_pending: bool,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum QuicklinkKind {
+    Url,
+    Command,
+    Folder,
+}
+
+#[derive(Clone)]
+struct Quicklink {
+    keyword: String,
+    kind: QuicklinkKind,
+    target: String,
+}
+
+impl Quicklink {
+    fn expand(&self, argument: &str) -> String {
+        match self.kind {
+            QuicklinkKind::Url => self.target.replace("{}", &urlencoding::encode(argument)),
+            QuicklinkKind::Command | QuicklinkKind::Folder => self.target.replace("{}", argument),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Deserialize)]
+struct PluginManifest {
+    name: String,
+    trigger: String,
+    executable: String,
+    #[serde(default)]
+    args: Vec<String>,
+    #[serde(default = "default_plugin_timeout")]
+    timeout_ms: u64,
+    #[serde(default)]
+    version: String,
+    #[serde(default)]
+    flint_version: String,
+    #[serde(default)]
+    settings: Vec<PluginSetting>,
+    #[serde(skip)]
+    dir: PathBuf,
+    #[serde(skip)]
+    enabled: bool,
+    #[serde(skip)]
+    values: serde_json::Map<String, serde_json::Value>,
+}
+
+impl PluginManifest {
+    fn id(&self) -> String {
+        self.dir.file_name()
+            .and_then(|n| n.to_str())
+            .unwrap_or(&self.name)
+            .to_string()
+    }
+}
+
+#[derive(Clone, Debug, Deserialize)]
+struct PluginSetting {
+    key: String,
+    #[serde(default)]
+    label: String,
+    #[serde(rename = "type", default)]
+    kind: String,
+    #[serde(default)]
+    default: serde_json::Value,
+}
+
+fn default_plugin_timeout() -> u64 {
+    2000
+}
+
+#[derive(Clone, Debug, Deserialize)]
+struct PluginResult {
+    title: String,
+    #[serde(default)]
+    subtitle: String,
+    #[serde(default)]
+    icon: String,
+    #[serde(default)]
+    actions: Vec<PluginAction>,
+}
+
+impl PluginResult {
+    fn message(title: String, subtitle: String) -> Self {
+        Self { title, subtitle, icon: String::new(), actions: Vec::new() }
+    }
+}
+
+#[derive(Clone, Debug, Deserialize)]
+struct PluginAction {
+    #[serde(rename = "type")]
+    kind: String,
+    value: String,
+}
+
+struct PluginResponse {
+    trigger: String,
+    query: String,
+    results: Option<Vec<PluginResult>>,
+}
+
+struct CurrencyResponse {
+    query: String,
+    result: Option<Option<(String, String, f64)>>,
+}
+
+#[derive(Clone)]
+struct ScriptResult {
+    title: String,
+    subtitle: String,
+    action: Option<usize>,
+}
+
+struct ScriptEngine {
+    lua: mlua::Lua,
+    http: Arc<Mutex<LuaHttpCache>>,
+}
+
+const LUA_HTTP_CACHE_TTL: Duration = Duration::from_secs(60);
+const LUA_HTTP_CACHE_MAX_ENTRIES: usize = 64;
+
+#[derive(Default)]
+struct LuaHttpCache {
+    responses: std::collections::HashMap<String, (Instant, Option<Result<String, String>>)>,
+    generation: u64,
+    egui_ctx: Option<egui::Context>,
+}
+
+impl LuaHttpCache {
+    fn pending(&self) -> bool {
+        self.responses.values().any(|(_, response)| response.is_none())
+    }
+}
+
+impl ScriptEngine {
+    fn load(client: reqwest::Client, handle: tokio::runtime::Handle) -> (Option<Self>, Vec<String>) {
+        let mut errors = Vec::new();
+        let scripts = list_lua_scripts();
+        if scripts.is_empty() {
+            return (None, errors);
         }
         
-        if ctx.input(|i| i.pointer.any_click()) {
-            if let Some(pos) = ctx.input(|i| i.pointer.interact_pos()) {
-                let rect = ctx.screen_rect();
-                if !rect.contains(pos) {
-                    self.should_close = true;
-                }
+        let lua = match new_script_lua() {
+            Ok(lua) => lua,
+            Err(e) => {
+                errors.push(format!("Failed to start Lua: {}", e));
+                return (None, errors);
             }
+        };
+        
+        let http = Arc::new(Mutex::new(LuaHttpCache::default()));
+        if let Err(e) = install_lua_api(&lua, client, handle, http.clone()) {
+            errors.push(format!("Failed to set up Lua API: {}", e));
+            return (None, errors);
         }
-
-        if self.has_focused {
-            if let Some(focused) = ctx.input(|i| i.viewport().focused) {
-                if !focused {
-                    self.should_close = true;
-                }
+        
+        for script in scripts {
+            let name = script.file_name().and_then(|n| n.to_str()).unwrap_or("script.lua").to_string();
+            let result = fs::read_to_string(&script)
+                .map_err(|e| e.to_string())
+                .and_then(|source| lua.load(source.as_str()).set_name(name.as_str()).exec().map_err(|e| e.to_string()));
+            if let Err(e) = result {
+                errors.push(format!("{}: {}", name, e));
             }
         }
         
-        if self.should_close {
-            ctx.send_viewport_cmd(egui::ViewportCommand::Close);
-            return;
+        (Some(Self { lua, http }), errors)
+    }
+    
+    fn http_state(&self) -> (u64, bool) {
+        self.http.lock()
+            .map(|http| (http.generation, http.pending()))
+            .unwrap_or_default()
+    }
+    
+    fn set_repaint_context(&self, ctx: egui::Context) {
+        if let Ok(mut http) = self.http.lock() {
+            http.egui_ctx = Some(ctx);
         }
-
-        let window_alpha = self.window_animation.ease_out();
+    }
+    
+    fn has_trigger(&self, trigger: &str) -> bool {
+        self.lua.named_registry_value::<mlua::Table>("flint_providers")
+            .and_then(|providers| providers.contains_key(trigger))
+            .unwrap_or(false)
+    }
+    
+    fn query(&self, trigger: &str, query: &str) -> Result<Vec<ResultType>, String> {
+        let run = || -> mlua::Result<Vec<ResultType>> {
+            let providers: mlua::Table = self.lua.named_registry_value("flint_providers")?;
+            let provider: mlua::Function = providers.get(trigger)?;
+            let actions = self.lua.create_table()?;
+            self.lua.set_named_registry_value("flint_actions", actions.clone())?;
+            
+            let items: Option<mlua::Table> = provider.call(query)?;
+            let mut results = Vec::new();
+            if let Some(items) = items {
+                for item in items.sequence_values::<mlua::Table>() {
+                    if let Some(result) = lua_table_to_result(item?, &actions)? {
+                        results.push(result);
+                    }
+                }
+            }
+            Ok(results)
+        };
         
-        let window_width = 600.0;
-        let search_box_height = 50.0;
-        let result_item_height = 44.0;
-        let max_visible_results = 8;
-        let visible_results = self.results.len().min(max_visible_results);
-        let results_height = if visible_results > 0 {
-            (visible_results as f32 * result_item_height) + 10.0
-        } else {
-            0.0
+        run().map_err(|e| e.to_string())
+    }
+    
+    fn run_action(&self, action: usize) -> Result<(), String> {
+        let run = || -> mlua::Result<()> {
+            let actions: mlua::Table = self.lua.named_registry_value("flint_actions")?;
+            let function: mlua::Function = actions.get(action)?;
+            function.call(())
         };
-        let total_height = search_box_height + results_height;
         
-        ctx.send_viewport_cmd(egui::ViewportCommand::InnerSize(egui::vec2(
-            window_width,
-            total_height
-        )));
+        run().map_err(|e| e.to_string())
+    }
+}
 
-        let bg_rgb = self.theme.hex_to_rgb(&self.theme.background);
-        let border_rgb = self.theme.hex_to_rgb(&self.theme.border_color);
-        
-        let bg_color = egui::Color32::from_rgba_premultiplied(
-            (bg_rgb[0] * 255.0 * window_alpha) as u8,
-            (bg_rgb[1] * 255.0 * window_alpha) as u8,
-            (bg_rgb[2] * 255.0 * window_alpha) as u8,
-            (window_alpha * 255.0) as u8,
-        );
-        
-        let border_color = egui::Color32::from_rgba_premultiplied(
-            (border_rgb[0] * 255.0 * window_alpha) as u8,
-            (border_rgb[1] * 255.0 * window_alpha) as u8,
-            (border_rgb[2] * 255.0 * window_alpha) as u8,
-            (window_alpha * 255.0) as u8,
-        );
-        
-        egui::CentralPanel::default()
-            .frame(egui::Frame::none()
-                .fill(bg_color)
-                .stroke(egui::Stroke::new(1.0, border_color))
-                .rounding(self.theme.border_radius)
-                .shadow(egui::epaint::Shadow {
-                    offset: egui::vec2(0.0, 2.0),
-                    blur: 8.0,
-                    spread: 0.0,
-                    color: egui::Color32::from_rgba_premultiplied(0, 0, 0, (50.0 * window_alpha) as u8),
-                }))
-            .show(ctx, |ui| {
-                ui.set_min_width(window_width);
-                ui.set_max_width(window_width);
-                
-                ui.vertical(|ui| {
-                    let text_rgb = self.theme.hex_to_rgb(&self.theme.text_color);
-                    
-                    ui.add_space(5.0);
-                    ui.add_space(5.0);
-                    
-                    let search_text_color = egui::Color32::from_rgba_premultiplied(
-                        (text_rgb[0] * 255.0 * window_alpha) as u8,
-                        (text_rgb[1] * 255.0 * window_alpha) as u8,
-                        (text_rgb[2] * 255.0 * window_alpha) as u8,
-                        (window_alpha * 255.0) as u8,
-                    );
-                    
-                    ui.horizontal(|ui| {
-                        ui.add_space(15.0);
-                        
-                        let response = ui.add_sized(
-                            [window_width - 30.0, 30.0],
-                            egui::TextEdit::singleline(&mut self.query)
-                                .hint_text("Search...")
-                                .frame(false)
-                                .text_color(search_text_color)
-                                .font(egui::FontId::proportional(20.0))
-                                .id(egui::Id::new("search_field"))
-                        );
+#[derive(Clone, Copy, PartialEq)]
+enum OutputStream {
+    Stdout,
+    Stderr,
+    Status,
+}
 
-                        if !self.has_focused {
-                            ui.ctx().memory_mut(|mem| mem.request_focus(response.id));
-                            self.has_focused = true;
-                        }
-                        
-                        ui.add_space(15.0);
-                    });
-                    
-                    if !self.results.is_empty() {
-                        ui.add_space(5.0);
-                        let separator_alpha = (window_alpha * 255.0) as u8;
-                        let border_rgb = self.theme.hex_to_rgb(&self.theme.border_color);
-                        let separator_color = egui::Color32::from_rgba_premultiplied(
-                            (border_rgb[0] * 255.0) as u8,
-                            (border_rgb[1] * 255.0) as u8,
-                            (border_rgb[2] * 255.0) as u8,
-                            separator_alpha
-                        );
-                        
-                        let separator_height = 1.0;
-                        let available_width = ui.available_width();
-                        let separator_rect = egui::Rect::from_min_size(
-                            ui.cursor().min,
-                            egui::vec2(available_width, separator_height)
-                        );
-                        ui.painter().rect_filled(separator_rect, 0.0, separator_color);
-                        
-                        ui.add_space(separator_height + 5.0);
-                    }
+#[derive(Clone)]
+struct OutputLine {
+    text: String,
+    stream: OutputStream,
+}
 
-                    if ui.input(|i| i.key_pressed(egui::Key::Escape)) {
-                        self.should_close = true;
-                    }
+impl OutputLine {
+    fn status(text: String) -> Self {
+        Self { text, stream: OutputStream::Status }
+    }
+}
 
-                    if ui.input(|i| i.key_pressed(egui::Key::ArrowDown)) && !self.results.is_empty() {
-                        self.selected = (self.selected + 1) % self.results.len();
-                    }
+struct InlineOutput {
+    command: String,
+    lines: Vec<OutputLine>,
+    finished: bool,
+}
 
-                    if ui.input(|i| i.key_pressed(egui::Key::ArrowUp)) && !self.results.is_empty() {
-                        if self.selected == 0 {
-                            self.selected = self.results.len() - 1;
-                        } else {
-                            self.selected -= 1;
-                        }
-                    }
+#[derive(Clone, Copy, PartialEq)]
+enum AppMode {
+    Launcher,
+    Settings,
+}
 
-                    if ui.input(|i| i.key_pressed(egui::Key::Enter)) && !self.results.is_empty() {
-                        if let Some(result) = self.results.get(self.selected) {
-                            execute_result(result);
-                            self.should_close = true;
-                        }
-                    }
+struct Theme {
+    background: String,
+    text_color: String,
+    selection_bg: String,
+    selection_text: String,
+    border_color: String,
+    font_size: f32,
+    border_radius: f32,
+    font_family: String,
+    highlight_color: String,
+}
 
-                    self.results.clear();
+impl Default for Theme {
+    fn default() -> Self {
+        Self {
+            background: "#2d2d30".to_string(),
+            text_color: "#ffffff".to_string(),
+            selection_bg: "#0078d4".to_string(),
+            selection_text: "#ffffff".to_string(),
+            border_color: "#3e3e42".to_string(),
+            font_size: 16.0,
+            border_radius: 2.0,
+            font_family: "Segoe UI".to_string(),
+            highlight_color: "#0078d4".to_string(),
+        }
+    }
+}
 
-                    if !self.query.is_empty() {
-                        if self.query.starts_with("file:") {
-                            let file_query = &self.query[5..].trim();
-                            if !file_query.is_empty() {
-                                let file_results = search_files(file_query);
-                                for path in file_results {
-                                    self.results.push(ResultType::File(path));
-                                }
-                            } else {
-                                self.results.push(ResultType::Command("Search files...".to_string()));
-                            }
-                        }
-                        else if self.query.starts_with("e:") {
-                            let emoji_query = &self.query[2..].trim();
-                            if !emoji_query.is_empty() {
-                                let emoji_results = search_emojis(emoji_query);
-                                for (name, emoji) in emoji_results {
-                                    self.results.push(ResultType::Emoji(name, emoji));
-                                }
-                            } else {
-                                self.results.push(ResultType::Command("Search emojis...".to_string()));
-                            }
-                        }
-                        else if let Some((from, to, result)) = self.runtime.block_on(convert_currency_online(&self.query)) {
-                            self.results.push(ResultType::Currency(from, to, result));
-                        }
-                        else if looks_like_url(&self.query) {
-                            let url = if self.query.contains("://") {
-                                self.query.clone()
-                            } else {
-                                format!("https://{}", self.query)
-                            };
-                            self.results.push(ResultType::Url(url));
-                        }
-                        else if is_calculation(&self.query) {
-                            let expr = self.query.trim();
-                            if !expr.is_empty() {
-                                match meval::eval_str(expr) {
-                                    Ok(result) => {
-                                        self.results.push(ResultType::Calculator(result.to_string()));
-                                    }
-                                    Err(_) => {}
-                                }
-                            }
-                        }
-                        else if self.query.starts_with('$') {
-                            let cmd = &self.query[1..].trim();
-                            if !cmd.is_empty() {
-                                self.results.push(ResultType::Command(cmd.to_string()));
-                            } else {
-                                self.results.push(ResultType::Command("Enter command...".to_string()));
-                            }
-                        }
-                        else if self.query.starts_with('@') {
-                            let search = &self.query[1..].trim();
-                            if !search.is_empty() {
-                                self.results.push(ResultType::WebSearch(search.to_string()));
-                            } else {
-                                self.results.push(ResultType::Command("Search the web...".to_string()));
+impl Theme {
+    fn load_from_config() -> Self {
+        let config_dir = get_config_dir();
+        let theme_path = config_dir.join("theme.conf");
+        
+        if !theme_path.exists() {
+            create_default_theme(&theme_path);
+            return Self::default();
+        }
+        
+        let mut theme = Self::default();
+        
+        if let Ok(content) = fs::read_to_string(&theme_path) {
+            for line in content.lines() {
+                let parts: Vec<&str> = line.splitn(2, '=').collect();
+                if parts.len() == 2 {
+                    let key = parts[0].trim();
+                    let value = parts[1].trim();
+                    
+                    match key {
+                        "background" => theme.background = value.to_string(),
+                        "text_color" => theme.text_color = value.to_string(),
+                        "selection_bg" => theme.selection_bg = value.to_string(),
+                        "selection_text" => theme.selection_text = value.to_string(),
+                        "border_color" => theme.border_color = value.to_string(),
+                        "highlight_color" => theme.highlight_color = value.to_string(),
+                        "font_size" => {
+                            if let Ok(size) = value.parse() {
+                                theme.font_size = size;
                             }
                         }
-                        
-                        if self.results.is_empty() {
-                            let matcher = SkimMatcherV2::default();
-                            let query = self.query.clone();
-                            
-                            let mut scored_results: Vec<(i64, AppEntry)> = self
-                                .items
-                                .par_iter()
-                                .filter_map(|app| {
-                                    if let Some((score, indices)) = matcher.fuzzy_indices(&app.name, &query) {
-                                        let mut app_with_match = app.clone();
-                                        app_with_match.match_indices = indices;
-                                        return Some((score + 100, app_with_match));
-                                    }
-                                    
-                                    if let Some((score, _)) = matcher.fuzzy_indices(&app.exec_command, &query) {
-                                        let mut app_with_match = app.clone();
-                                        app_with_match.match_indices = Vec::new();
-                                        return Some((score, app_with_match));
-                                    }
-                                    
-                                    None
-                                })
-                                .collect();
-                            
-                            scored_results.sort_by(|a, b| b.0.cmp(&a.0));
-                            
-                            for (_, app) in scored_results.into_iter().take(max_visible_results) {
-                                self.results.push(ResultType::App(app));
-                            }
-                            
-                            if self.results.is_empty() {
-                                self.results.push(ResultType::WebSearch(query));
+                        "border_radius" => {
+                            if let Ok(radius) = value.parse() {
+                                theme.border_radius = radius;
                             }
                         }
-                        
-                        if self.selected >= self.results.len() && !self.results.is_empty() {
-                            self.selected = 0;
-                        }
+                        "font_family" => theme.font_family = value.to_string(),
+                        _ => {}
                     }
+                }
+            }
+        }
+        
+        theme
+    }
+    
+    fn hex_to_rgb(&self, hex: &str) -> [f32; 3] {
+        let hex = hex.trim_start_matches('#');
+        if hex.len() == 6 {
+            if let (Ok(r), Ok(g), Ok(b)) = (
+                u8::from_str_radix(&hex[0..2], 16),
+                u8::from_str_radix(&hex[2..4], 16),
+                u8::from_str_radix(&hex[4..6], 16),
+            ) {
+                return [r as f32 / 255.0, g as f32 / 255.0, b as f32 / 255.0];
+            }
+        }
+        [0.0, 0.0, 0.0]
+    }
+}
 
-                    if !self.results.is_empty() {
-                        egui::ScrollArea::vertical()
-                            .max_height(result_item_height * max_visible_results as f32)
-                            .scroll_bar_visibility(egui::scroll_area::ScrollBarVisibility::AlwaysHidden)
-                            .show(ui, |ui| {
-                                for (i, result) in self.results.iter().enumerate() {
-                                    let is_selected = i == self.selected;
-                                    let item_alpha = self.get_result_alpha(i);
-                                    let item_offset = self.get_result_offset(i);
-                                    
-                                    let sel_bg_rgb = self.theme.hex_to_rgb(&self.theme.selection_bg);
-                                    
-                                    let item_bg = if is_selected {
-                                        egui::Color32::from_rgba_premultiplied(
-                                            (sel_bg_rgb[0] * 255.0 * item_alpha) as u8,
-                                            (sel_bg_rgb[1] * 255.0 * item_alpha) as u8,
-                                            (sel_bg_rgb[2] * 255.0 * item_alpha) as u8,
-                                            (item_alpha * 255.0) as u8,
-                                        )
-                                    } else {
-                                        egui::Color32::TRANSPARENT
-                                    };
-                                    
-                                    ui.add_space(item_offset);
-                                    
-                                    let item_frame = egui::Frame::none()
-                                        .fill(item_bg)
-                                        .inner_margin(egui::Margin::symmetric(15.0, 8.0));
-                                    
-                                    let response = item_frame.show(ui, |ui| {
-                                        ui.set_min_height(result_item_height - 16.0);
-                                        ui.set_width(window_width);
-                                        
-                                        ui.horizontal(|ui| {
-                                            render_result_item(ui, result, is_selected, &self.theme, item_alpha, &self.query);
-                                        });
-                                    }).response;
-                                    
-                                    if is_selected {
-                                        response.scroll_to_me(Some(egui::Align::Center));
-                                    }
-                                    
-                                    if response.clicked() {
-                                        execute_result(result);
-                                    }
-                                    
-                                    ui.add_space(-item_offset);
-                                }
-                            });
+#[derive(Clone)]
+enum ResultType {
+    App(AppEntry),
+    AppAction(AppEntry, DesktopAction),
+    Calculator(String),
+    Command(String),
+    ShellCommand(String, RunMode),
+    InlineCommand(String),
+    Output(OutputLine),
+    Snippet(Snippet),
+    Quicklink(Quicklink, String),
+    Game(SteamGame),
+    Plugin(PluginResult),
+    Script(ScriptResult),
+    WebSearch(String),
+    Url(String),
+    File(PathBuf),
+    Emoji(String, String),
+    Currency(String, String, f64),
+    Suggestion(Box<ResultType>),
+}
+
+impl ResultType {
+    fn provider(&self) -> &'static str {
+        match self {
+            ResultType::App(_) | ResultType::AppAction(_, _) => "apps",
+            ResultType::Calculator(_) => "calculator",
+            ResultType::Command(_) => "hint",
+            ResultType::ShellCommand(_, _) => "commands",
+            ResultType::InlineCommand(_) | ResultType::Output(_) => "inline",
+            ResultType::Snippet(_) => "snippets",
+            ResultType::Quicklink(_, _) => "quicklinks",
+            ResultType::Game(_) => "games",
+            ResultType::Plugin(_) => "plugin",
+            ResultType::Script(_) => "script",
+            ResultType::WebSearch(_) => "web",
+            ResultType::Url(_) => "url",
+            ResultType::File(_) => "files",
+            ResultType::Emoji(_, _) => "emoji",
+            ResultType::Currency(_, _, _) => "currency",
+            ResultType::Suggestion(_) => "suggestion",
+        }
+    }
+    
+    fn learning_key(&self) -> Option<String> {
+        match self {
+            ResultType::App(app) => Some(format!("app:{}", app.desktop_id)),
+            ResultType::AppAction(app, action) => Some(format!("action:{}:{}", app.desktop_id, action.name)),
+            ResultType::Quicklink(quicklink, _) => Some(format!("quicklink:{}", quicklink.keyword)),
+            ResultType::Game(game) => Some(format!("game:{}", game.app_id)),
+            ResultType::File(path) => Some(format!("file:{}", path.display())),
+            ResultType::Emoji(emoji, _) => Some(format!("emoji:{}", emoji)),
+            ResultType::Suggestion(suggestion) => suggestion.learning_key(),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Clone, Default)]
+struct NormalizedText {
+    text: String,
+    origin: Vec<usize>,
+}
+
+impl NormalizedText {
+    fn new(text: &str, transliterate: bool) -> Self {
+        let mut normalized = Self::default();
+        
+        for (index, c) in text.chars().enumerate() {
+            if transliterate && !c.is_ascii() && !is_combining_mark(c) {
+                let ascii = any_ascii::any_ascii_char(c);
+                if !ascii.is_empty() {
+                    for ascii_char in ascii.chars() {
+                        normalized.push(ascii_char, index);
+                    }
+                    continue;
+                }
+            }
+            
+            for decomposed in std::iter::once(c).nfkd().filter(|d| !is_combining_mark(*d)) {
+                normalized.push(decomposed, index);
+            }
+        }
+        
+        normalized
+    }
+    
+    fn push(&mut self, c: char, origin: usize) {
+        for folded in c.to_lowercase() {
+            self.text.push(folded);
+            self.origin.push(origin);
+        }
+    }
+    
+    fn original_indices(&self, indices: &[usize]) -> Vec<usize> {
+        let mut original: Vec<usize> = indices.iter()
+            .filter_map(|&i| self.origin.get(i).copied())
+            .collect();
+        original.dedup();
+        original
+    }
+}
+
+fn fold_text(text: &str, transliterate: bool) -> String {
+    NormalizedText::new(text, transliterate).text
+}
+
+fn char_mask(text: &str) -> u64 {
+    text.chars()
+        .filter(|c| !c.is_whitespace())
+        .fold(0, |mask, c| mask | 1 << (c as u32 % 64))
+}
+
+struct SearchField {
+    text: String,
+    char_mask: u64,
+}
+
+impl SearchField {
+    fn new(text: &str, transliterate: bool) -> Self {
+        let text = fold_text(text, transliterate);
+        Self { char_mask: char_mask(&text), text }
+    }
+}
+
+struct AppSearchText {
+    name: NormalizedText,
+    name_mask: u64,
+    initials: Vec<(usize, Option<char>)>,
+    generic_name: SearchField,
+    keywords: Vec<SearchField>,
+    categories: Vec<SearchField>,
+    comment: SearchField,
+    exec: SearchField,
+    actions: Vec<SearchField>,
+    char_mask: u64,
+}
+
+impl AppSearchText {
+    fn new(app: &AppEntry, transliterate: bool) -> Self {
+        let chars: Vec<char> = app.name.chars().collect();
+        let initials = (0..chars.len())
+            .filter(|&i| is_word_start(&chars, i))
+            .map(|i| (i, fold_text(&chars[i].to_string(), transliterate).chars().next()))
+            .collect();
+        
+        let name = NormalizedText::new(&app.name, transliterate);
+        let mut search = Self {
+            name_mask: char_mask(&name.text),
+            name,
+            initials,
+            generic_name: SearchField::new(&app.generic_name, transliterate),
+            keywords: app.keywords.iter().map(|k| SearchField::new(k, transliterate)).collect(),
+            categories: app.categories.iter().map(|c| SearchField::new(c, transliterate)).collect(),
+            comment: SearchField::new(&app.comment, transliterate),
+            exec: SearchField::new(&app.exec_command, transliterate),
+            actions: app.actions.iter()
+                .map(|action| SearchField::new(&format!("{} {}", app.name, action.name), transliterate))
+                .collect(),
+            char_mask: 0,
+        };
+        search.char_mask = [&search.generic_name, &search.comment, &search.exec].into_iter()
+            .chain(&search.keywords)
+            .chain(&search.categories)
+            .chain(&search.actions)
+            .fold(search.name_mask, |mask, field| mask | field.char_mask);
+        search
+    }
+    
+    fn may_match(&self, query: &AppQuery) -> bool {
+        let contains = |text: &str| {
+            let mut chars = text.chars();
+            query.initials.iter().all(|q| chars.any(|c| c == *q))
+        };
+        query.may_match(self.char_mask)
+            && (contains(&self.name.text)
+                || [&self.generic_name, &self.comment, &self.exec].into_iter()
+                    .chain(&self.keywords)
+                    .chain(&self.categories)
+                    .chain(&self.actions)
+                    .any(|field| contains(&field.text)))
+    }
+}
+
+struct AppQuery {
+    text: String,
+    initials: Vec<char>,
+    char_mask: u64,
+}
+
+impl AppQuery {
+    fn new(query: &str, transliterate: bool) -> Self {
+        let text = fold_text(query, transliterate);
+        Self {
+            initials: text.chars().filter(|c| !c.is_whitespace()).collect(),
+            char_mask: char_mask(&text),
+            text,
+        }
+    }
+    
+    fn may_match(&self, mask: u64) -> bool {
+        self.char_mask & !mask == 0
+    }
+}
+
+const SEARCH_CACHE_MAX_ENTRIES: usize = 256;
+
+#[derive(Clone, Default)]
+struct CachedSearch {
+    candidates: Option<Vec<usize>>,
+    results: Vec<(i64, ResultType)>,
+}
+
+#[derive(Default)]
+struct SearchCache {
+    generation: u64,
+    entries: std::collections::HashMap<(&'static str, String), CachedSearch>,
+}
+
+impl SearchCache {
+    fn sync(&mut self, generation: u64) {
+        if self.generation != generation || self.entries.len() >= SEARCH_CACHE_MAX_ENTRIES {
+            self.entries.clear();
+            self.generation = generation;
+        }
+    }
+    
+    fn get(&self, provider: &'static str, query: &str) -> Option<&CachedSearch> {
+        self.entries.get(&(provider, query.to_string()))
+    }
+    
+    fn narrowest(&self, provider: &'static str, query: &str) -> Option<&[usize]> {
+        self.entries.iter()
+            .filter(|((cached_provider, cached_query), _)| *cached_provider == provider && query.starts_with(cached_query.as_str()))
+            .filter_map(|((_, cached_query), cached)| Some((cached_query.len(), cached.candidates.as_deref()?)))
+            .max_by_key(|(len, _)| *len)
+            .map(|(_, candidates)| candidates)
+    }
+    
+    fn insert(&mut self, provider: &'static str, query: &str, cached: CachedSearch) {
+        self.entries.insert((provider, query.to_string()), cached);
+    }
+}
+
+fn build_app_index(apps: &[AppEntry], transliterate: bool) -> Vec<AppSearchText> {
+    apps.par_iter()
+        .map(|app| AppSearchText::new(app, transliterate))
+        .collect()
+}
+
+#[derive(Clone)]
+struct AppEntry {
+    name: String,
+    desktop_id: String,
+    exec_command: String,
+    match_indices: Vec<usize>,
+    source: AppSource,
+    generic_name: String,
+    keywords: Vec<String>,
+    categories: Vec<String>,
+    comment: String,
+    actions: Vec<DesktopAction>,
+    terminal: bool,
+    icon: String,
+    working_dir: Option<PathBuf>,
+    desktop_file: Option<PathBuf>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum AppSource {
+    Native,
+    #[cfg(not(target_os = "windows"))]
+    Flatpak,
+    #[cfg(not(target_os = "windows"))]
+    Snap,
+    Link,
+}
+
+impl AppSource {
+    fn badge(&self) -> Option<&'static str> {
+        match self {
+            AppSource::Native => None,
+            #[cfg(not(target_os = "windows"))]
+            AppSource::Flatpak => Some("Flatpak"),
+            #[cfg(not(target_os = "windows"))]
+            AppSource::Snap => Some("Snap"),
+            AppSource::Link => Some("Link"),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+struct DesktopAction {
+    name: String,
+    exec: String,
+}
+
+#[cfg(not(target_os = "windows"))]
+#[derive(Default)]
+struct DesktopEntry {
+    name: String,
+    generic_name: String,
+    keywords: Vec<String>,
+    categories: Vec<String>,
+    comment: String,
+    exec: String,
+    icon: String,
+    path: String,
+    actions: Vec<DesktopAction>,
+    hidden: bool,
+    no_display: bool,
+    terminal: bool,
+    flatpak: bool,
+    snap: bool,
+}
+
+struct AnimationState {
+    progress: f32,
+    start_time: Instant,
+    duration: Duration,
+    animation_type: AnimationType,
+}
+
+impl AnimationState {
+    fn new(duration: Duration, animation_type: AnimationType) -> Self {
+        Self {
+            progress: 0.0,
+            start_time: Instant::now(),
+            duration,
+            animation_type,
+        }
+    }
+    
+    fn update(&mut self) -> bool {
+        let elapsed = self.start_time.elapsed();
+        self.progress = (elapsed.as_millis() as f32 / self.duration.as_millis() as f32).min(1.0);
+        self.progress < 1.0
+    }
+    
+    fn ease_out(&self) -> f32 {
+        1.0 - (1.0 - self.progress).powf(2.0)
+    }
+}
+
+#[derive(Clone, Copy)]
+enum AnimationType {
+    FadeIn,
+    SlideDown,
+}
+
+struct FlintApp {
+    query: String,
+    results: Vec<ResultType>,
+    items: Vec<AppEntry>,
+    app_index: Vec<AppSearchText>,
+    search_cache: SearchCache,
+    index_generation: u64,
+    matcher: Box<dyn FuzzyMatcher>,
+    selected: usize,
+    should_close: bool,
+    has_focused: bool,
+    theme: Theme,
+    _lock_file: File,
+    window_animation: AnimationState,
+    result_animations: Vec<AnimationState>,
+    runtime: tokio::runtime::Runtime,
+    app_mode: AppMode,
+    hotkey_config: Arc<Mutex<HotkeyConfig>>,
+    temp_launcher_key: String,
+    temp_settings_key: String,
+    temp_enabled: bool,
+    status_message: String,
+    status_color: egui::Color32,
+    message_time: Instant,
+    command_config: CommandConfig,
+    command_history: CommandHistory,
+    selection_memory: SelectionMemory,
+    path_executables: Option<Vec<String>>,
+    inline_output: Option<Arc<Mutex<InlineOutput>>>,
+    search_config: SearchConfig,
+    snippet_config: SnippetConfig,
+    snippets: Option<Vec<Snippet>>,
+    snippet_form: Option<SnippetForm>,
+    quicklinks: Vec<Quicklink>,
+    games: Vec<SteamGame>,
+    plugins: Vec<PluginManifest>,
+    plugin_response: Option<Arc<Mutex<PluginResponse>>>,
+    currency_response: Option<Arc<Mutex<CurrencyResponse>>>,
+    plugin_archive_path: String,
+    http_client: reqwest::Client,
+    scripts: Option<ScriptEngine>,
+    script_cache: Option<(String, u64, Vec<ResultType>)>,
+    logged_query: String,
+    results_query: Option<String>,
+    results_pending: bool,
+    egui_ctx: Option<egui::Context>,
+}
+
+fn start_tray_thread() {
+    thread::spawn(move || {
+        #[cfg(target_os = "windows")]
+        {
+            use tray_item::TrayItem;
+            
+            if let Ok(mut tray) = TrayItem::new("Flint Launcher", "") {
+                let _ = tray.add_label("Flint Launcher");
+                let _ = tray.inner_mut().add_separator();
+                
+                let _ = tray.add_menu_item("Show Launcher", || {
+                    if let Ok(exe_path) = std::env::current_exe() {
+                        let _ = Command::new(exe_path).spawn();
                     }
                 });
-        });
+                
+                let _ = tray.add_menu_item("Settings", || {
+                    if let Ok(exe_path) = std::env::current_exe() {
+                        let _ = Command::new(exe_path).arg("settings").spawn();
+                    }
+                });
+                
+                let _ = tray.inner_mut().add_separator();
+                
+                let _ = tray.add_menu_item("Open Config Folder", || {
+                    let config_dir = get_config_dir();
+                    if let Err(e) = open_file(&config_dir) {
+                        error!("{}", e);
+                    }
+                });
+                
+                let _ = tray.add_menu_item("Open Log", || {
+                    if let Err(e) = open_log() {
+                        error!("{}", e);
+                    }
+                });
+                
+                let _ = tray.inner_mut().add_separator();
+                
+                let _ = tray.add_menu_item("Show Theme File", || {
+                    let theme_path = get_config_dir().join("theme.conf");
+                    if let Err(e) = open_file(&theme_path) {
+                        error!("{}", e);
+                    }
+                });
+                
+                let _ = tray.inner_mut().add_separator();
+                
+                let _ = tray.add_menu_item("Exit", || {
+                    std::process::exit(0);
+                });
+            }
+        }
+        
+        loop {
+            thread::sleep(Duration::from_secs(1));
+        }
+    });
+}
+
+impl FlintApp {
+    fn new() -> Result<Self, String> {
+        let lock_file = acquire_lock()?;
+        let scan_started = Instant::now();
+        let items = scan_apps();
+        let search_config = SearchConfig::load();
+        let app_index = build_app_index(&items, search_config.transliterate);
+        info!(apps = items.len(), elapsed_ms = scan_started.elapsed().as_millis() as u64, "indexed applications");
+        let runtime = tokio::runtime::Runtime::new()
+            .map_err(|e| format!("Failed to create async runtime: {}", e))?;
+        let http_client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(10))
+            .build()
+            .map_err(|e| format!("Failed to create HTTP client: {}", e))?;
+        let (scripts, script_errors) = ScriptEngine::load(http_client.clone(), runtime.handle().clone());
+        for e in &script_errors {
+            warn!("Lua script error: {}", e);
+        }
+        
+        Ok(Self {
+            query: String::new(),
+            results: Vec::new(),
+            items,
+            app_index,
+            search_cache: SearchCache::default(),
+            index_generation: 0,
+            matcher: search_config.backend.matcher(),
+            selected: 0,
+            should_close: false,
+            has_focused: false,
+            theme: Theme::load_from_config(),
+            _lock_file: lock_file,
+            window_animation: AnimationState::new(Duration::from_millis(300), AnimationType::FadeIn),
+            result_animations: Vec::new(),
+            runtime,
+            app_mode: AppMode::Launcher,
+            hotkey_config: Arc::new(Mutex::new(HotkeyConfig::load())),
+            temp_launcher_key: String::new(),
+            temp_settings_key: String::new(),
+            temp_enabled: false,
+            status_message: script_errors.join("; "),
+            status_color: egui::Color32::from_rgb(255, 120, 120),
+            message_time: Instant::now(),
+            command_config: CommandConfig::load(),
+            command_history: CommandHistory::load(),
+            selection_memory: SelectionMemory::load(),
+            path_executables: None,
+            inline_output: None,
+            search_config,
+            snippet_config: SnippetConfig::load(),
+            snippets: None,
+            snippet_form: None,
+            quicklinks: load_quicklinks(),
+            games: scan_steam_games(),
+            plugins: load_plugins(),
+            plugin_response: None,
+            currency_response: None,
+            plugin_archive_path: String::new(),
+            http_client,
+            scripts,
+            script_cache: None,
+            logged_query: String::new(),
+            results_query: None,
+            results_pending: false,
+            egui_ctx: None,
+        })
+    }
+    
+    fn update_result_animations(&mut self) {
+        if self.result_animations.len() != self.results.len() {
+            self.result_animations = self.results.iter()
+                .enumerate()
+                .map(|(i, _)| {
+                    let delay = Duration::from_millis((i * 40) as u64).min(Duration::from_millis(200));
+                    let mut anim = AnimationState::new(Duration::from_millis(250), AnimationType::SlideDown);
+                    anim.start_time += delay;
+                    anim
+                })
+                .collect();
+        }
+        
+        for anim in &mut self.result_animations {
+            anim.update();
+        }
+    }
+    
+    fn get_result_offset(&self, index: usize) -> f32 {
+        self.result_animations.get(index)
+            .map(|anim| {
+                match anim.animation_type {
+                    AnimationType::SlideDown => (1.0 - anim.ease_out()) * -30.0,
+                    _ => 0.0,
+                }
+            })
+            .unwrap_or(0.0)
+    }
+    
+    fn get_result_alpha(&self, index: usize) -> f32 {
+        self.result_animations.get(index)
+            .map(|anim| anim.ease_out())
+            .unwrap_or(1.0)
+    }
+}
+
+impl eframe::App for FlintApp {
+    fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
+        if self.should_close {
+            ctx.send_viewport_cmd(egui::ViewportCommand::Close);
+            return;
+        }
+        
+        match self.app_mode {
+            AppMode::Settings => self.render_settings(ctx),
+            AppMode::Launcher => self.render_launcher(ctx),
+        }
+    }
+}
+
+impl FlintApp {
+    fn render_launcher(&mut self, ctx: &egui::Context) {
+        let _window_animating = self.window_animation.update();
+        self.update_result_animations();
+        
+        let still_animating = self.window_animation.progress < 1.0 || self.result_animations.iter().any(|a| a.progress < 1.0);
+        
+        if still_animating {
+            ctx.request_repaint();
+        }
+        
+        if ctx.input(|i| i.pointer.any_click()) {
+            if let Some(pos) = ctx.input(|i| i.pointer.interact_pos()) {
+                let rect = ctx.screen_rect();
+                if !rect.contains(pos) {
+                    self.should_close = true;
+                }
+            }
+        }
+
+        if self.has_focused {
+            if let Some(focused) = ctx.input(|i| i.viewport().focused) {
+                if !focused {
+                    self.should_close = true;
+                }
+            }
+        }
+        
+        if self.should_close {
+            ctx.send_viewport_cmd(egui::ViewportCommand::Close);
+            return;
+        }
+
+        let window_alpha = self.window_animation.ease_out();
+        
+        let window_width = 600.0;
+        let search_box_height = 50.0;
+        let result_item_height = 44.0;
+        let max_visible_results = 8;
+        let visible_results = match &self.snippet_form {
+            Some(form) => form.fields.len() + 1,
+            None => self.results.len().min(max_visible_results),
+        };
+        let results_height = if visible_results > 0 {
+            (visible_results as f32 * result_item_height) + 10.0
+        } else {
+            0.0
+        };
+        let status_visible = !self.status_message.is_empty() && self.message_time.elapsed().as_secs() < 4;
+        let total_height = search_box_height + results_height + if status_visible { 24.0 } else { 0.0 };
+        
+        ctx.send_viewport_cmd(egui::ViewportCommand::InnerSize(egui::vec2(
+            window_width,
+            total_height
+        )));
+
+        let bg_rgb = self.theme.hex_to_rgb(&self.theme.background);
+        let border_rgb = self.theme.hex_to_rgb(&self.theme.border_color);
+        
+        let bg_color = egui::Color32::from_rgba_premultiplied(
+            (bg_rgb[0] * 255.0 * window_alpha) as u8,
+            (bg_rgb[1] * 255.0 * window_alpha) as u8,
+            (bg_rgb[2] * 255.0 * window_alpha) as u8,
+            (window_alpha * 255.0) as u8,
+        );
+        
+        let border_color = egui::Color32::from_rgba_premultiplied(
+            (border_rgb[0] * 255.0 * window_alpha) as u8,
+            (border_rgb[1] * 255.0 * window_alpha) as u8,
+            (border_rgb[2] * 255.0 * window_alpha) as u8,
+            (window_alpha * 255.0) as u8,
+        );
+        
+        egui::CentralPanel::default()
+            .frame(egui::Frame::none()
+                .fill(bg_color)
+                .stroke(egui::Stroke::new(1.0, border_color))
+                .rounding(self.theme.border_radius)
+                .shadow(egui::epaint::Shadow {
+                    offset: egui::vec2(0.0, 2.0),
+                    blur: 8.0,
+                    spread: 0.0,
+                    color: egui::Color32::from_rgba_premultiplied(0, 0, 0, (50.0 * window_alpha) as u8),
+                }))
+            .show(ctx, |ui| {
+                ui.set_min_width(window_width);
+                ui.set_max_width(window_width);
+                
+                ui.vertical(|ui| {
+                    let text_rgb = self.theme.hex_to_rgb(&self.theme.text_color);
+                    
+                    ui.add_space(5.0);
+                    ui.add_space(5.0);
+                    
+                    let search_text_color = egui::Color32::from_rgba_premultiplied(
+                        (text_rgb[0] * 255.0 * window_alpha) as u8,
+                        (text_rgb[1] * 255.0 * window_alpha) as u8,
+                        (text_rgb[2] * 255.0 * window_alpha) as u8,
+                        (window_alpha * 255.0) as u8,
+                    );
+                    
+                    ui.horizontal(|ui| {
+                        ui.add_space(15.0);
+                        
+                        let response = ui.add_sized(
+                            [window_width - 30.0, 30.0],
+                            egui::TextEdit::singleline(&mut self.query)
+                                .hint_text("Search...")
+                                .frame(false)
+                                .text_color(search_text_color)
+                                .font(egui::FontId::proportional(20.0))
+                                .lock_focus(true)
+                                .id(egui::Id::new("search_field"))
+                        );
+
+                        if !self.has_focused {
+                            ui.ctx().memory_mut(|mem| mem.request_focus(response.id));
+                            self.has_focused = true;
+                        }
+                        
+                        ui.add_space(15.0);
+                    });
+                    
+                    if self.snippet_form.is_some() {
+                        self.render_snippet_form(ui, window_width, search_text_color);
+                        return;
+                    }
+                    
+                    if !self.results.is_empty() {
+                        ui.add_space(5.0);
+                        let separator_alpha = (window_alpha * 255.0) as u8;
+                        let border_rgb = self.theme.hex_to_rgb(&self.theme.border_color);
+                        let separator_color = egui::Color32::from_rgba_premultiplied(
+                            (border_rgb[0] * 255.0) as u8,
+                            (border_rgb[1] * 255.0) as u8,
+                            (border_rgb[2] * 255.0) as u8,
+                            separator_alpha
+                        );
+                        
+                        let separator_height = 1.0;
+                        let available_width = ui.available_width();
+                        let separator_rect = egui::Rect::from_min_size(
+                            ui.cursor().min,
+                            egui::vec2(available_width, separator_height)
+                        );
+                        ui.painter().rect_filled(separator_rect, 0.0, separator_color);
+                        
+                        ui.add_space(separator_height + 5.0);
+                    }
+
+                    if ui.input(|i| i.key_pressed(egui::Key::Escape)) {
+                        self.should_close = true;
+                    }
+
+                    if ui.input(|i| i.key_pressed(egui::Key::ArrowDown)) && !self.results.is_empty() {
+                        self.selected = (self.selected + 1) % self.results.len();
+                    }
+
+                    if ui.input(|i| i.key_pressed(egui::Key::ArrowUp)) && !self.results.is_empty() {
+                        if self.selected == 0 {
+                            self.selected = self.results.len() - 1;
+                        } else {
+                            self.selected -= 1;
+                        }
+                    }
+
+                    if ui.input(|i| i.key_pressed(egui::Key::Enter)) && !self.results.is_empty() {
+                        if let Some(result) = self.results.get(self.selected).cloned() {
+                            self.activate_result(&result);
+                        }
+                    }
+                    
+                    if ui.input(|i| i.key_pressed(egui::Key::Tab)) {
+                        if let Some(ResultType::ShellCommand(cmd, _)) = self.results.get(self.selected) {
+                            self.query = format!("${}", cmd);
+                            move_cursor_to_end(ui.ctx(), egui::Id::new("search_field"), &self.query);
+                        }
+                    }
+
+                    let query_changed = self.results_query.as_deref() != Some(self.query.as_str());
+                    if query_changed || self.results_pending {
+                        self.refresh_results(max_visible_results);
+                        if query_changed || !self.results_pending {
+                            ui.ctx().request_repaint();
+                        }
+                    }
+
+                    let mut clicked = None;
+                    
+                    if !self.results.is_empty() {
+                        egui::ScrollArea::vertical()
+                            .max_height(result_item_height * max_visible_results as f32)
+                            .scroll_bar_visibility(egui::scroll_area::ScrollBarVisibility::AlwaysHidden)
+                            .show(ui, |ui| {
+                                for (i, result) in self.results.iter().enumerate() {
+                                    let is_selected = i == self.selected;
+                                    let item_alpha = self.get_result_alpha(i);
+                                    let item_offset = self.get_result_offset(i);
+                                    
+                                    let sel_bg_rgb = self.theme.hex_to_rgb(&self.theme.selection_bg);
+                                    
+                                    let item_bg = if is_selected {
+                                        egui::Color32::from_rgba_premultiplied(
+                                            (sel_bg_rgb[0] * 255.0 * item_alpha) as u8,
+                                            (sel_bg_rgb[1] * 255.0 * item_alpha) as u8,
+                                            (sel_bg_rgb[2] * 255.0 * item_alpha) as u8,
+                                            (item_alpha * 255.0) as u8,
+                                        )
+                                    } else {
+                                        egui::Color32::TRANSPARENT
+                                    };
+                                    
+                                    ui.add_space(item_offset);
+                                    
+                                    let item_frame = egui::Frame::none()
+                                        .fill(item_bg)
+                                        .inner_margin(egui::Margin::symmetric(15.0, 8.0));
+                                    
+                                    let response = item_frame.show(ui, |ui| {
+                                        ui.set_min_height(result_item_height - 16.0);
+                                        ui.set_width(window_width);
+                                        
+                                        ui.horizontal(|ui| {
+                                            render_result_item(ui, result, is_selected, &self.theme, item_alpha, &self.query);
+                                        });
+                                    }).response;
+                                    
+                                    if is_selected {
+                                        response.scroll_to_me(Some(egui::Align::Center));
+                                    }
+                                    
+                                    if response.clicked() {
+                                        clicked = Some(result.clone());
+                                    }
+                                    
+                                    ui.add_space(-item_offset);
+                                }
+                            });
+                    }
+                    
+                    if status_visible {
+                        ui.horizontal(|ui| {
+                            ui.add_space(15.0);
+                            ui.label(
+                                egui::RichText::new(&self.status_message)
+                                    .color(self.status_color)
+                                    .size(self.theme.font_size * 0.8)
+                            );
+                        });
+                    }
+                    
+                    if let Some(result) = clicked {
+                        self.activate_result(&result);
+                    }
+                });
+        });
+        
+        if status_visible {
+            ctx.request_repaint_after(Duration::from_secs(4).saturating_sub(self.message_time.elapsed()));
+        }
+    }
+    
+    fn refresh_results(&mut self, max_visible_results: usize) {
+        let search_started = Instant::now();
+        let mut pending = false;
+        self.results.clear();
+
+        if !self.query.is_empty() {
+            if self.query.starts_with("file:") {
+                let file_query = self.query[5..].trim().to_string();
+                if !file_query.is_empty() {
+                    let file_results = self.cached_search("files", &file_query, |app, _| CachedSearch {
+                        candidates: None,
+                        results: search_files(&file_query, app.search_config.transliterate)
+                            .into_iter()
+                            .map(|path| (0, ResultType::File(path)))
+                            .collect(),
+                    });
+                    self.results.extend(file_results.into_iter().map(|(_, result)| result));
+                    self.selection_memory.rank_learned(&self.query, &mut self.results);
+                    if self.results.is_empty() {
+                        self.results.push(ResultType::Command("No matching files".to_string()));
+                    }
+                } else {
+                    self.results.push(ResultType::Command("Search files...".to_string()));
+                }
+            }
+            else if self.query.starts_with("e:") {
+                let emoji_query = &self.query[2..].trim();
+                if !emoji_query.is_empty() {
+                    let emoji_results = search_emojis(emoji_query, self.search_config.transliterate);
+                    for (name, emoji) in emoji_results {
+                        self.results.push(ResultType::Emoji(name, emoji));
+                    }
+                    self.selection_memory.rank_learned(&self.query, &mut self.results);
+                    if self.results.is_empty() {
+                        self.results.push(ResultType::Command("No matching emojis".to_string()));
+                    }
+                } else {
+                    self.results.push(ResultType::Command("Search emojis...".to_string()));
+                }
+            }
+            else if self.query.starts_with("sn:") {
+                let snippet_query = self.query[3..].trim().to_lowercase();
+                let snippets = self.snippets.get_or_insert_with(load_snippets);
+                for snippet in snippets.iter()
+                    .filter(|snippet| snippet.name.to_lowercase().contains(&snippet_query))
+                    .take(max_visible_results)
+                {
+                    self.results.push(ResultType::Snippet(snippet.clone()));
+                }
+                
+                if self.results.is_empty() {
+                    self.results.push(ResultType::Command("No matching snippets".to_string()));
+                }
+            }
+            else if self.query.starts_with('>') {
+                let cmd = self.query[1..].trim().to_string();
+                if cmd.is_empty() {
+                    self.results.push(ResultType::Command("Enter command to run inline...".to_string()));
+                } else {
+                    let output = self.inline_output.as_ref()
+                        .and_then(|output| output.lock().ok())
+                        .filter(|output| output.command == cmd)
+                        .map(|output| (output.finished, output.lines.clone()));
+                    
+                    match output {
+                        Some((true, lines)) => {
+                            self.results.extend(lines.into_iter().map(ResultType::Output));
+                            self.results.push(ResultType::InlineCommand(cmd));
+                        }
+                        Some((false, _)) => {
+                            self.results.push(ResultType::Output(OutputLine::status(format!("⏳ Running {}...", cmd))));
+                            pending = true;
+                        }
+                        None => {
+                            self.results.push(ResultType::InlineCommand(cmd));
+                        }
+                    }
+                }
+            }
+            else if let Some(results) = self.query_scripts() {
+                self.results.extend(results);
+                pending = self.scripts.as_ref().is_some_and(|scripts| scripts.http_state().1);
+            }
+            else if let Some((plugin, plugin_query)) = find_plugin(&self.plugins, &self.query) {
+                let plugin = plugin.clone();
+                let response = self.plugin_response.as_ref()
+                    .and_then(|response| response.lock().ok())
+                    .filter(|response| response.trigger == plugin.trigger && response.query == plugin_query)
+                    .map(|response| response.results.clone());
+                
+                match response {
+                    Some(Some(results)) if results.is_empty() => {
+                        self.results.push(ResultType::Plugin(PluginResult::message(
+                            format!("No results from {}", plugin.name),
+                            String::new(),
+                        )));
+                    }
+                    Some(Some(results)) => {
+                        self.results.extend(results.into_iter().map(ResultType::Plugin));
+                    }
+                    Some(None) => {
+                        self.results.push(ResultType::Plugin(PluginResult::message(
+                            format!("⏳ Searching {}...", plugin.name),
+                            String::new(),
+                        )));
+                        pending = true;
+                    }
+                    None => {
+                        self.query_plugin(plugin.clone(), plugin_query);
+                        self.results.push(ResultType::Plugin(PluginResult::message(
+                            format!("⏳ Searching {}...", plugin.name),
+                            String::new(),
+                        )));
+                        pending = true;
+                    }
+                }
+            }
+            else if self.query.starts_with('$') {
+                let cmd = self.query[1..].trim().to_string();
+                let default_mode = self.command_config.run_mode;
+                if !cmd.is_empty() {
+                    self.results.push(ResultType::ShellCommand(cmd.clone(), default_mode));
+                    for mode in RunMode::ALL.into_iter().filter(|m| *m != default_mode) {
+                        self.results.push(ResultType::ShellCommand(cmd.clone(), mode));
+                    }
+                } else {
+                    self.results.push(ResultType::Command("Enter command...".to_string()));
+                }
+                
+                for entry in self.command_history.matching(&cmd).take(5) {
+                    self.results.push(ResultType::ShellCommand(entry.clone(), default_mode));
+                }
+                
+                if !cmd.is_empty() && !cmd.contains(char::is_whitespace) {
+                    let executables = self.path_executables.get_or_insert_with(scan_path_executables);
+                    for name in executables.iter().filter(|name| name.starts_with(&cmd) && **name != cmd).take(max_visible_results) {
+                        let completion = ResultType::ShellCommand(name.clone(), default_mode);
+                        if !self.results.iter().any(|r| matches!(r, ResultType::ShellCommand(c, _) if c == name)) {
+                            self.results.push(completion);
+                        }
+                    }
+                }
+            }
+            else if self.query.starts_with('@') {
+                let search = &self.query[1..].trim();
+                if !search.is_empty() {
+                    self.results.push(ResultType::WebSearch(search.to_string()));
+                } else {
+                    self.results.push(ResultType::Command("Search the web...".to_string()));
+                }
+            }
+            else {
+                let (blended, currency_pending) = self.blended_results(max_visible_results);
+                self.results.extend(blended);
+                pending = currency_pending;
+            }
+            
+            if self.selected >= self.results.len() && !self.results.is_empty() {
+                self.selected = 0;
+            }
+        }
+        
+        if self.query != self.logged_query {
+            debug!(
+                query = %self.query,
+                provider = self.results.first().map(ResultType::provider).unwrap_or("none"),
+                results = self.results.len(),
+                elapsed_ms = search_started.elapsed().as_secs_f64() * 1000.0,
+                "search"
+            );
+            self.logged_query = self.query.clone();
+        }
+        
+        self.results_query = Some(self.query.clone());
+        self.results_pending = pending;
+    }
+    
+    fn activate_result(&mut self, result: &ResultType) {
+        debug!(provider = result.provider(), query = %self.query, "activating result");
+        match result {
+            ResultType::Suggestion(suggestion) => {
+                let suggestion = suggestion.as_ref().clone();
+                self.activate_result(&suggestion);
+                return;
+            }
+            ResultType::InlineCommand(cmd) => {
+                self.run_inline_command(cmd);
+                return;
+            }
+            ResultType::Output(line) if line.stream == OutputStream::Status => {
+                let text = self.inline_output.as_ref().and_then(|o| o.lock().ok()).map(|output| {
+                    let lines: Vec<&str> = output.lines.iter()
+                        .filter(|l| l.stream != OutputStream::Status)
+                        .map(|l| l.text.as_str())
+                        .collect();
+                    lines.join("\n")
+                });
+                if let Some(text) = text {
+                    let copied = copy_to_clipboard(&text);
+                    self.report_failure(copied);
+                }
+                return;
+            }
+            ResultType::Output(line) => {
+                let copied = copy_to_clipboard(&line.text);
+                self.report_failure(copied);
+                return;
+            }
+            ResultType::ShellCommand(cmd, _) => {
+                self.command_history.record(cmd, self.command_config.history_size);
+            }
+            ResultType::Plugin(plugin_result) if plugin_result.actions.is_empty() => {
+                return;
+            }
+            ResultType::Script(script_result) => {
+                let outcome = match (&self.scripts, script_result.action) {
+                    (Some(scripts), Some(action)) => scripts.run_action(action),
+                    _ => return,
+                };
+                if self.report_failure(outcome.map_err(|e| format!("Script error: {}", e))) {
+                    self.should_close = true;
+                }
+                return;
+            }
+            ResultType::Snippet(snippet) => {
+                let fields: Vec<(String, String)> = snippet_inputs(&snippet.body)
+                    .into_iter()
+                    .map(|name| (name, String::new()))
+                    .collect();
+                
+                if fields.is_empty() {
+                    self.insert_snippet(snippet, &[]);
+                } else {
+                    self.snippet_form = Some(SnippetForm {
+                        snippet: snippet.clone(),
+                        fields,
+                        focus_pending: true,
+                    });
+                }
+                return;
+            }
+            _ => {}
+        }
+        
+        let outcome = execute_result(result, &self.command_config);
+        if self.report_failure(outcome) {
+            if let Some(key) = result.learning_key() {
+                self.selection_memory.record(&self.query, key);
+                self.index_generation += 1;
+            }
+            self.should_close = true;
+        }
+    }
+    
+    fn report_failure(&mut self, outcome: Result<(), String>) -> bool {
+        match outcome {
+            Ok(()) => true,
+            Err(e) => {
+                error!("{}", e);
+                self.show_status(format!("⚠ {}", e), egui::Color32::from_rgb(255, 120, 120));
+                false
+            }
+        }
+    }
+    
+    fn insert_snippet(&mut self, snippet: &Snippet, inputs: &[(String, String)]) {
+        let text = expand_snippet(&snippet.body, inputs, &self.snippet_config);
+        let mut outcome = copy_to_clipboard(&text);
+        
+        if outcome.is_ok() && self.snippet_config.auto_paste {
+            outcome = paste_to_previous_window(self.snippet_config.paste_delay_ms);
+        }
+        
+        if self.report_failure(outcome) {
+            self.should_close = true;
+        }
+    }
+    
+    fn render_snippet_form(&mut self, ui: &mut egui::Ui, window_width: f32, text_color: egui::Color32) {
+        let Some(form) = self.snippet_form.as_mut() else {
+            return;
+        };
+        
+        ui.add_space(5.0);
+        ui.horizontal(|ui| {
+            ui.add_space(15.0);
+            ui.label(
+                egui::RichText::new(format!("📋 {}", form.snippet.name))
+                    .color(text_color)
+                    .size(self.theme.font_size)
+            );
+        });
+        
+        for (i, (name, value)) in form.fields.iter_mut().enumerate() {
+            ui.horizontal(|ui| {
+                ui.add_space(15.0);
+                ui.add_sized(
+                    [140.0, 28.0],
+                    egui::Label::new(egui::RichText::new(name.as_str()).color(text_color).size(self.theme.font_size))
+                );
+                let response = ui.add_sized(
+                    [window_width - 185.0, 28.0],
+                    egui::TextEdit::singleline(value).id(egui::Id::new(("snippet_field", i)))
+                );
+                
+                if i == 0 && form.focus_pending {
+                    response.request_focus();
+                    form.focus_pending = false;
+                }
+            });
+        }
+        
+        if ui.input(|i| i.key_pressed(egui::Key::Escape)) {
+            self.snippet_form = None;
+            ui.ctx().memory_mut(|mem| mem.request_focus(egui::Id::new("search_field")));
+        } else if ui.input(|i| i.key_pressed(egui::Key::Enter)) {
+            if let Some(form) = self.snippet_form.take() {
+                self.insert_snippet(&form.snippet, &form.fields);
+            }
+        }
+    }
+    
+    fn show_status(&mut self, message: String, color: egui::Color32) {
+        self.status_message = message;
+        self.status_color = color;
+        self.message_time = Instant::now();
+        if let Some(ctx) = &self.egui_ctx {
+            ctx.request_repaint();
+        }
+    }
+    
+    fn query_scripts(&mut self) -> Option<Vec<ResultType>> {
+        let scripts = self.scripts.as_ref()?;
+        let (trigger, script_query) = self.query.split_once(' ').unwrap_or((&self.query, ""));
+        if !scripts.has_trigger(trigger) {
+            return None;
+        }
+        
+        let (http_generation, _) = scripts.http_state();
+        if let Some((cached_query, generation, results)) = &self.script_cache {
+            if *cached_query == self.query && *generation == http_generation {
+                return Some(results.clone());
+            }
+        }
+        
+        let results = match scripts.query(trigger, script_query.trim()) {
+            Ok(results) => results,
+            Err(e) => {
+                warn!(trigger, "Lua provider failed: {}", e);
+                self.show_status(format!("⚠ Script error: {}", e), egui::Color32::from_rgb(255, 120, 120));
+                Vec::new()
+            }
+        };
+        self.script_cache = Some((self.query.clone(), http_generation, results.clone()));
+        Some(results)
+    }
+    
+    fn cached_search(
+        &mut self,
+        provider: &'static str,
+        query: &str,
+        search: impl FnOnce(&Self, Option<&[usize]>) -> CachedSearch,
+    ) -> Vec<(i64, ResultType)> {
+        self.search_cache.sync(self.index_generation);
+        if let Some(cached) = self.search_cache.get(provider, query) {
+            return cached.results.clone();
+        }
+        
+        let candidates = self.search_cache.narrowest(provider, query).map(|candidates| candidates.to_vec());
+        let cached = search(self, candidates.as_deref());
+        let results = cached.results.clone();
+        self.search_cache.insert(provider, query, cached);
+        results
+    }
+    
+    fn matched_apps(&mut self, query: &AppQuery) -> Vec<(i64, ResultType)> {
+        self.cached_search("apps", &query.text, |app, candidates| {
+            match_apps(
+                &app.items,
+                &app.app_index,
+                query,
+                app.matcher.as_ref(),
+                &app.search_config,
+                &app.selection_memory.learned_apps(&app.query),
+                candidates,
+            )
+        })
+    }
+    
+    fn blended_results(&mut self, max_results: usize) -> (Vec<ResultType>, bool) {
+        let app_query = AppQuery::new(self.query.trim(), self.search_config.transliterate);
+        let app_matches = if self.search_config.provider_enabled("apps") {
+            self.matched_apps(&app_query)
+        } else {
+            Vec::new()
+        };
+        let suggestions = if app_matches.is_empty() && self.search_config.provider_enabled("suggestions") {
+            self.cached_search("suggestions", &app_query.text, |app, _| CachedSearch {
+                candidates: None,
+                results: typo_suggestions(&app.items, &app.app_index, &app.quicklinks, &app_query.text)
+                    .into_iter()
+                    .map(|suggestion| (0, suggestion))
+                    .collect(),
+            })
+        } else {
+            Vec::new()
+        };
+        let query = self.query.trim().to_string();
+        let (currency, currency_pending) = if self.search_config.provider_enabled("currency") {
+            self.currency_conversion(&query)
+        } else {
+            (None, false)
+        };
+        
+        let config = &self.search_config;
+        let mut providers: Vec<(&str, Vec<(f64, ResultType)>)> = Vec::new();
+        
+        if config.provider_enabled("calculator") && is_calculation(&query) {
+            if let Ok(result) = meval::eval_str(&query) {
+                providers.push(("calculator", vec![(1.0, ResultType::Calculator(result.to_string()))]));
+            }
+        }
+        
+        if let Some((from, to, result)) = currency {
+            providers.push(("currency", vec![(1.0, ResultType::Currency(from, to, result))]));
+        }
+        
+        if config.provider_enabled("url") && looks_like_url(&query) {
+            let (relevance, url) = if query.contains("://") {
+                (0.9, query.clone())
+            } else {
+                (0.7, format!("https://{}", query))
+            };
+            providers.push(("url", vec![(relevance, ResultType::Url(url))]));
+        }
+        
+        let matcher = self.matcher.as_ref();
+        let relevance = |score: i64| (score as f64 / APP_SCORE_SCALE).min(1.0);
+        
+        let app_results: Vec<(f64, ResultType)> = if config.provider_enabled("apps") {
+            app_matches.into_iter()
+                .map(|(score, result)| (relevance(score), result))
+                .collect()
+        } else {
+            Vec::new()
+        };
+        
+        let quicklink_results: Vec<(f64, ResultType)> = if config.provider_enabled("quicklinks") {
+            match_quicklinks(&self.quicklinks, &query, matcher)
+                .into_iter()
+                .map(|(score, result)| (relevance(score), result))
+                .collect()
+        } else {
+            Vec::new()
+        };
+        
+        let game_results: Vec<(f64, ResultType)> = if config.provider_enabled("games") {
+            match_games(&self.games, &app_query.text, matcher, config.transliterate)
+                .into_iter()
+                .map(|(score, result)| (relevance(score), result))
+                .collect()
+        } else {
+            Vec::new()
+        };
+        
+        providers.push(("suggestions", suggestion_results(
+            &[&app_results, &quicklink_results, &game_results],
+            suggestions.into_iter().map(|(_, suggestion)| suggestion).collect(),
+        )));
+        providers.push(("apps", app_results));
+        providers.push(("quicklinks", quicklink_results));
+        providers.push(("games", game_results));
+        
+        if config.provider_enabled("web") {
+            providers.push(("web", vec![(0.05, ResultType::WebSearch(query.clone()))]));
+        }
+        
+        let boosts = self.selection_memory.boosts(&query);
+        (blend_results(config, &boosts, providers, max_results), currency_pending)
+    }
+    
+    fn currency_conversion(&mut self, query: &str) -> (Option<(String, String, f64)>, bool) {
+        let Some((amount, from, to)) = parse_currency_query(query) else {
+            return (None, false);
+        };
+        let known = self.currency_response.as_ref()
+            .and_then(|response| response.lock().ok())
+            .filter(|response| response.query == query)
+            .map(|response| response.result.clone());
+        if let Some(result) = known {
+            let pending = result.is_none();
+            return (result.flatten(), pending);
+        }
+        
+        let response = Arc::new(Mutex::new(CurrencyResponse {
+            query: query.to_string(),
+            result: None,
+        }));
+        self.currency_response = Some(response.clone());
+        let client = self.http_client.clone();
+        let egui_ctx = self.egui_ctx.clone();
+        
+        self.runtime.spawn(async move {
+            let result = convert_currency_online(amount, &from, &to, &client).await;
+            if let Ok(mut response) = response.lock() {
+                response.result = Some(result);
+            }
+            if let Some(ctx) = egui_ctx {
+                ctx.request_repaint();
+            }
+        });
+        (None, true)
+    }
+    
+    fn query_plugin(&mut self, plugin: PluginManifest, query: String) {
+        let response = Arc::new(Mutex::new(PluginResponse {
+            trigger: plugin.trigger.clone(),
+            query: query.clone(),
+            results: None,
+        }));
+        self.plugin_response = Some(response.clone());
+        let egui_ctx = self.egui_ctx.clone();
+        
+        self.runtime.spawn(async move {
+            let started = Instant::now();
+            let results = match run_plugin(&plugin, &query).await {
+                Ok(results) => {
+                    debug!(
+                        plugin = %plugin.name,
+                        results = results.len(),
+                        elapsed_ms = started.elapsed().as_millis() as u64,
+                        "plugin query"
+                    );
+                    results
+                }
+                Err(e) => {
+                    warn!(plugin = %plugin.name, "plugin query failed: {}", e);
+                    vec![PluginResult::message(format!("⚠ {} failed", plugin.name), e)]
+                }
+            };
+            if let Ok(mut response) = response.lock() {
+                response.results = Some(results);
+            }
+            if let Some(ctx) = egui_ctx {
+                ctx.request_repaint();
+            }
+        });
+    }
+    
+    fn run_inline_command(&mut self, cmd: &str) {
+        let output = Arc::new(Mutex::new(InlineOutput {
+            command: cmd.to_string(),
+            lines: Vec::new(),
+            finished: false,
+        }));
+        self.inline_output = Some(output.clone());
+        self.selected = 0;
+        self.results_query = None;
+        
+        let cmd = cmd.to_string();
+        let timeout = Duration::from_secs(self.command_config.output_timeout_secs);
+        let egui_ctx = self.egui_ctx.clone();
+        
+        self.runtime.spawn(async move {
+            let lines = capture_command_output(&cmd, timeout).await;
+            if let Ok(mut output) = output.lock() {
+                output.lines = lines;
+                output.finished = true;
+            }
+            if let Some(ctx) = egui_ctx {
+                ctx.request_repaint();
+            }
+        });
+    }
+    
+    fn render_plugin_manager(&mut self, ui: &mut egui::Ui) {
+        ui.heading("🧩 Plugins");
+        ui.label(format!("Plugin folder: {}", get_plugins_dir().display()));
+        
+        if self.plugins.is_empty() {
+            ui.label("No plugins installed.");
+        }
+        
+        let mut states_changed = false;
+        let mut saved_settings = None;
+        
+        for plugin in &mut self.plugins {
+            ui.horizontal(|ui| {
+                let label = format!("{} {}", plugin.name, plugin.version);
+                if ui.checkbox(&mut plugin.enabled, label).changed() {
+                    states_changed = true;
+                }
+                ui.label(format!("trigger: {}", plugin.trigger));
+            });
+            
+            if let Err(e) = check_plugin_compatibility(plugin) {
+                ui.colored_label(egui::Color32::YELLOW, format!("⚠ {}", e));
+            }
+            
+            if !plugin.settings.is_empty() {
+                egui::CollapsingHeader::new(format!("{} settings", plugin.name))
+                    .id_source(&plugin.dir)
+                    .show(ui, |ui| {
+                        for setting in &plugin.settings {
+                            let value = plugin.values
+                                .entry(setting.key.clone())
+                                .or_insert_with(|| setting.default.clone());
+                            render_plugin_setting(ui, setting, value);
+                        }
+                        
+                        if ui.button("💾 Save Plugin Settings").clicked() {
+                            saved_settings = Some(save_plugin_settings(plugin).map(|_| plugin.name.clone()));
+                        }
+                    });
+            }
+        }
+        
+        if states_changed {
+            save_plugin_states(&self.plugins);
+        }
+        
+        match saved_settings {
+            Some(Ok(name)) => self.show_status(format!("✓ Saved settings for {}", name), egui::Color32::GREEN),
+            Some(Err(e)) => self.show_status(format!("✗ {}", e), egui::Color32::RED),
+            None => {}
+        }
+        
+        ui.label("Install plugin from archive (.zip):");
+        ui.horizontal(|ui| {
+            ui.text_edit_singleline(&mut self.plugin_archive_path);
+            
+            if ui.button("📦 Install").clicked() {
+                match install_plugin_archive(&expand_home(self.plugin_archive_path.trim())) {
+                    Ok(name) => {
+                        self.plugins = load_plugins();
+                        self.plugin_archive_path.clear();
+                        self.show_status(format!("✓ Installed {}", name), egui::Color32::GREEN);
+                    }
+                    Err(e) => self.show_status(format!("✗ {}", e), egui::Color32::RED),
+                }
+            }
+        });
+    }
+    
+    fn render_settings(&mut self, ctx: &egui::Context) {
+        if let Ok(cfg) = self.hotkey_config.lock() {
+            self.temp_launcher_key = cfg.launcher_key.clone();
+            self.temp_settings_key = cfg.settings_key.clone();
+            self.temp_enabled = cfg.enabled;
+        }
+        
+        egui::CentralPanel::default().show(ctx, |ui| {
+            egui::ScrollArea::vertical().show(ui, |ui| {
+                ui.heading("⚙️ Flint Launcher Settings");
+                ui.separator();
+                
+                ui.heading("⌨️ Hotkey Configuration");
+                
+                ui.label("Launcher Hotkey:");
+                ui.text_edit_singleline(&mut self.temp_launcher_key);
+                ui.label("Example: Alt+Space, Ctrl+`, Super+Shift+D");
+                
+                ui.separator();
+                
+                ui.label("Settings Hotkey:");
+                ui.text_edit_singleline(&mut self.temp_settings_key);
+                ui.label("Example: Alt+Shift+S");
+                
+                ui.separator();
+                
+                ui.checkbox(&mut self.temp_enabled, "Enable Hotkeys");
+                
+                ui.separator();
+                
+                ui.heading("💻 Command Mode");
+                
+                ui.label("Default action for $ commands:");
+                ui.horizontal(|ui| {
+                    for mode in RunMode::ALL {
+                        ui.radio_value(&mut self.command_config.run_mode, mode, mode.label());
+                    }
+                });
+                
+                ui.label("Terminal emulator (auto to detect):");
+                ui.text_edit_singleline(&mut self.command_config.terminal);
+                
+                ui.label("Terminal arguments ({} = command, empty for the emulator default):");
+                ui.text_edit_singleline(&mut self.command_config.terminal_args);
+                
+                if ui.button("💾 Save Command Mode").clicked() {
+                    self.command_config.save();
+                    
+                    self.status_message = "✓ Command mode saved!".to_string();
+                    self.status_color = egui::Color32::GREEN;
+                    self.message_time = Instant::now();
+                }
+                
+                ui.separator();
+                
+                ui.heading("🔍 Search");
+                
+                ui.label("Matcher:");
+                ui.horizontal(|ui| {
+                    for backend in MatcherBackend::ALL {
+                        ui.radio_value(&mut self.search_config.backend, backend, backend.label());
+                    }
+                });
+                
+                ui.checkbox(&mut self.search_config.transliterate, "Match non-Latin names by their Latin spelling (pinyin, romaji, ...)");
+                
+                ui.label("Field weights (%):");
+                egui::Grid::new("search_weights").num_columns(2).show(ui, |ui| {
+                    for (field, weight) in self.search_config.weights_mut() {
+                        ui.label(field.replace('_', " "));
+                        ui.add(egui::DragValue::new(weight).clamp_range(0..=200).suffix("%"));
+                        ui.end_row();
+                    }
+                });
+                
+                ui.label("Mixed results (weight %, max results):");
+                egui::Grid::new("search_providers").num_columns(3).show(ui, |ui| {
+                    for (name, settings) in &mut self.search_config.providers {
+                        ui.label(name.as_str());
+                        ui.add(egui::DragValue::new(&mut settings.weight).clamp_range(0..=200).suffix("%"));
+                        ui.add(egui::DragValue::new(&mut settings.max_results).clamp_range(0..=20));
+                        ui.end_row();
+                    }
+                });
+                
+                if ui.button("💾 Save Search").clicked() {
+                    self.search_config.save();
+                    self.app_index = build_app_index(&self.items, self.search_config.transliterate);
+                    self.index_generation += 1;
+                    self.matcher = self.search_config.backend.matcher();
+                    
+                    self.status_message = "✓ Search settings saved!".to_string();
+                    self.status_color = egui::Color32::GREEN;
+                    self.message_time = Instant::now();
+                }
+                
+                ui.horizontal(|ui| {
+                    ui.label(format!("Learned selections: {}", self.selection_memory.entries.len()));
+                    if ui.button("🗑 Reset Learned Results").clicked() {
+                        self.selection_memory.reset();
+                        self.index_generation += 1;
+                        
+                        self.status_message = "✓ Learned results cleared".to_string();
+                        self.status_color = egui::Color32::GREEN;
+                        self.message_time = Instant::now();
+                    }
+                });
+                
+                ui.separator();
+                
+                ui.heading("📋 Snippets");
+                ui.label(format!("Snippet folder: {}", get_snippets_dir().display()));
+                
+                if ui.checkbox(&mut self.snippet_config.auto_paste, "Paste snippets into the previous window").changed() {
+                    self.snippet_config.save();
+                }
+                
+                ui.separator();
+                
+                self.render_plugin_manager(ui);
+                
+                ui.separator();
+                
+                ui.heading("🎨 Theme Configuration");
+                ui.label(format!("Theme file location: {}", get_config_dir().join("theme.conf").display()));
+                ui.label("Edit this file to change colors, fonts, and appearance.");
+                
+                ui.separator();
+                
+                if self.message_time.elapsed().as_secs() < 4 {
+                    ui.colored_label(self.status_color, &self.status_message);
+                }
+                
+                ui.separator();
+                
+                ui.horizontal(|ui| {
+                    if ui.button("💾 Save Hotkeys").clicked() {
+                        if let Ok(mut config) = self.hotkey_config.lock() {
+                            config.launcher_key = self.temp_launcher_key.clone();
+                            config.settings_key = self.temp_settings_key.clone();
+                            config.enabled = self.temp_enabled;
+                            config.save();
+                            
+                            self.status_message = "✓ Hotkeys saved! Restart to apply.".to_string();
+                            self.status_color = egui::Color32::GREEN;
+                            self.message_time = Instant::now();
+                        }
+                    }
+                    
+                    if ui.button("🔄 Reset Hotkeys").clicked() {
+                        let defaults = HotkeyConfig::default();
+                        self.temp_launcher_key = defaults.launcher_key.clone();
+                        self.temp_settings_key = defaults.settings_key.clone();
+                        self.temp_enabled = defaults.enabled;
+                        
+                        self.status_message = "Reset to defaults".to_string();
+                        self.status_color = egui::Color32::YELLOW;
+                        self.message_time = Instant::now();
+                    }
+                    
+                    if ui.button("📁 Open Config Folder").clicked() {
+                        let config_dir = get_config_dir();
+                        let opened = open_file(&config_dir);
+                        self.report_failure(opened);
+                    }
+                    
+                    if ui.button("📄 Open Log").clicked() {
+                        let opened = open_log();
+                        self.report_failure(opened);
+                    }
+                });
+                
+                ui.separator();
+                if ui.button("Back to Launcher").clicked() {
+                    self.app_mode = AppMode::Launcher;
+                }
+            });
+        });
+    }
+}
+
+fn suggestion_results(matches: &[&[(f64, ResultType)]], suggestions: Vec<ResultType>) -> Vec<(f64, ResultType)> {
+    if matches.iter().any(|results| !results.is_empty()) {
+        return Vec::new();
+    }
+    suggestions.into_iter()
+        .enumerate()
+        .map(|(i, suggestion)| (0.2 - i as f64 * 0.01, ResultType::Suggestion(Box::new(suggestion))))
+        .collect()
+}
+
+fn blend_results(
+    config: &SearchConfig,
+    boosts: &std::collections::HashMap<&str, f64>,
+    providers: Vec<(&str, Vec<(f64, ResultType)>)>,
+    max_results: usize,
+) -> Vec<ResultType> {
+    let mut blended: Vec<(f64, ResultType)> = Vec::new();
+    for (provider, mut scored) in providers {
+        let settings = config.provider(provider);
+        for (relevance, result) in &mut scored {
+            if let Some(boost) = result.learning_key().and_then(|key| boosts.get(key.as_str()).copied()) {
+                *relevance += boost;
+            }
+        }
+        scored.sort_by(|a, b| b.0.total_cmp(&a.0));
+        blended.extend(
+            scored.into_iter()
+                .take(settings.max_results)
+                .map(|(relevance, result)| (relevance * settings.weight as f64 / 100.0, result))
+        );
+    }
+    
+    blended.sort_by(|a, b| b.0.total_cmp(&a.0));
+    if let Some(web) = blended.iter().position(|(_, result)| matches!(result, ResultType::WebSearch(_))) {
+        if web >= max_results {
+            let web_result = blended.remove(web);
+            blended.insert(max_results - 1, web_result);
+        }
+    }
+    
+    let mut results: Vec<ResultType> = blended.into_iter()
+        .take(max_results)
+        .map(|(_, result)| result)
+        .collect();
+    
+    if let Some(ResultType::App(top_app)) = results.first().cloned() {
+        for (offset, action) in top_app.actions.iter().enumerate() {
+            results.insert(1 + offset, ResultType::AppAction(top_app.clone(), action.clone()));
+        }
+    }
+    
+    results
+}
+
+fn render_result_item(
+    ui: &mut egui::Ui,
+    result: &ResultType,
+    is_selected: bool,
+    theme: &Theme,
+    item_alpha: f32,
+    query: &str,
+) {
+    let text_rgb = theme.hex_to_rgb(&theme.text_color);
+    let sel_text_rgb = theme.hex_to_rgb(&theme.selection_text);
+    
+    let color = if is_selected { sel_text_rgb } else { text_rgb };
+    let color_val = egui::Color32::from_rgba_premultiplied(
+        (color[0] * 255.0 * item_alpha) as u8,
+        (color[1] * 255.0 * item_alpha) as u8,
+        (color[2] * 255.0 * item_alpha) as u8,
+        (item_alpha * 255.0) as u8,
+    );
+    
+    match result {
+        ResultType::App(app) => {
+            if let Some(texture) = (app.source == AppSource::Link).then(|| link_icon_texture(ui.ctx(), &app.icon)).flatten() {
+                let size = theme.font_size * 1.2;
+                ui.add(
+                    egui::Image::new(egui::load::SizedTexture::from_handle(&texture))
+                        .fit_to_exact_size(egui::vec2(size, size))
+                        .tint(egui::Color32::from_white_alpha((item_alpha * 255.0) as u8))
+                );
+            }
+            render_highlighted_text(ui, &app.name, &app.match_indices, is_selected, theme, item_alpha);
+            if let Some(badge) = app.source.badge() {
+                ui.label(
+                    egui::RichText::new(badge)
+                        .color(color_val.gamma_multiply(0.6))
+                        .size(theme.font_size * 0.7)
+                );
+            }
+            if !app.comment.is_empty() {
+                ui.label(
+                    egui::RichText::new(&app.comment)
+                        .color(color_val.gamma_multiply(0.6))
+                        .size(theme.font_size * 0.8)
+                );
+            }
+        }
+        ResultType::Suggestion(suggestion) => {
+            let name = match suggestion.as_ref() {
+                ResultType::App(app) => app.name.as_str(),
+                ResultType::Quicklink(link, _) => link.keyword.as_str(),
+                _ => "",
+            };
+            ui.label(
+                egui::RichText::new(format!("💡 Did you mean {}?", name))
+                    .color(color_val)
+                    .size(theme.font_size)
+            );
+        }
+        ResultType::AppAction(app, action) => {
+            ui.add_space(20.0);
+            ui.label(
+                egui::RichText::new(format!("{} › {}", app.name, action.name))
+                    .color(color_val)
+                    .size(theme.font_size * 0.9)
+            );
+        }
+        ResultType::Calculator(res) => {
+            ui.label(
+                egui::RichText::new(format!("🧮 {} = {}", query, res))
+                    .color(color_val)
+                    .size(theme.font_size)
+            );
+        }
+        ResultType::Command(cmd) => {
+            ui.label(
+                egui::RichText::new(format!("💻 {}", cmd))
+                    .color(color_val)
+                    .size(theme.font_size)
+            );
+        }
+        ResultType::ShellCommand(cmd, mode) => {
+            ui.label(
+                egui::RichText::new(format!("💻 {}", cmd))
+                    .color(color_val)
+                    .size(theme.font_size)
+            );
+            ui.label(
+                egui::RichText::new(mode.label())
+                    .color(color_val.gamma_multiply(0.6))
+                    .size(theme.font_size * 0.8)
+            );
+        }
+        ResultType::InlineCommand(cmd) => {
+            ui.label(
+                egui::RichText::new(format!("▶ Run inline: {}", cmd))
+                    .color(color_val)
+                    .size(theme.font_size)
+            );
+        }
+        ResultType::Output(line) => {
+            let text = match line.stream {
+                OutputStream::Status => egui::RichText::new(&line.text).size(theme.font_size),
+                OutputStream::Stdout => egui::RichText::new(&line.text).monospace(),
+                OutputStream::Stderr => egui::RichText::new(&line.text).monospace().italics(),
+            };
+            ui.label(text.color(color_val));
+        }
+        ResultType::Snippet(snippet) => {
+            let preview: String = snippet.body.lines().next().unwrap_or("").chars().take(40).collect();
+            ui.label(
+                egui::RichText::new(format!("📋 {}", snippet.name))
+                    .color(color_val)
+                    .size(theme.font_size)
+            );
+            ui.label(
+                egui::RichText::new(preview)
+                    .color(color_val.gamma_multiply(0.6))
+                    .size(theme.font_size * 0.8)
+            );
+        }
+        ResultType::Quicklink(link, argument) => {
+            let icon = match link.kind {
+                QuicklinkKind::Url => "🔗",
+                QuicklinkKind::Command => "💻",
+                QuicklinkKind::Folder => "📁",
+            };
+            ui.label(
+                egui::RichText::new(format!("{} {}", icon, link.keyword))
+                    .color(color_val)
+                    .size(theme.font_size)
+            );
+            ui.label(
+                egui::RichText::new(link.expand(argument))
+                    .color(color_val.gamma_multiply(0.6))
+                    .size(theme.font_size * 0.8)
+            );
+        }
+        ResultType::Game(game) => {
+            ui.label(
+                egui::RichText::new(format!("🎮 {}", game.name))
+                    .color(color_val)
+                    .size(theme.font_size)
+            );
+            ui.label(
+                egui::RichText::new("Steam")
+                    .color(color_val.gamma_multiply(0.6))
+                    .size(theme.font_size * 0.8)
+            );
+        }
+        ResultType::Plugin(plugin_result) => {
+            let icon = if !plugin_result.icon.is_empty() && plugin_result.icon.chars().count() <= 2 {
+                plugin_result.icon.as_str()
+            } else {
+                "🧩"
+            };
+            ui.label(
+                egui::RichText::new(format!("{} {}", icon, plugin_result.title))
+                    .color(color_val)
+                    .size(theme.font_size)
+            );
+            if !plugin_result.subtitle.is_empty() {
+                ui.label(
+                    egui::RichText::new(&plugin_result.subtitle)
+                        .color(color_val.gamma_multiply(0.6))
+                        .size(theme.font_size * 0.8)
+                );
+            }
+        }
+        ResultType::Script(script_result) => {
+            ui.label(
+                egui::RichText::new(format!("📜 {}", script_result.title))
+                    .color(color_val)
+                    .size(theme.font_size)
+            );
+            if !script_result.subtitle.is_empty() {
+                ui.label(
+                    egui::RichText::new(&script_result.subtitle)
+                        .color(color_val.gamma_multiply(0.6))
+                        .size(theme.font_size * 0.8)
+                );
+            }
+        }
+        ResultType::WebSearch(search_query) => {
+            ui.label(
+                egui::RichText::new(format!("🔍 Search DuckDuckGo: {}", search_query))
+                    .color(color_val)
+                    .size(theme.font_size)
+            );
+        }
+        ResultType::Url(url) => {
+            ui.label(
+                egui::RichText::new(format!("🌐 Open: {}", url))
+                    .color(color_val)
+                    .size(theme.font_size)
+            );
+        }
+        ResultType::File(path) => {
+            let file_name = path.file_name().and_then(|n| n.to_str()).unwrap_or("Unknown");
+            let parent_dir = path.parent()
+                .and_then(|p| p.file_name())
+                .and_then(|n| n.to_str())
+                .unwrap_or("");
+            ui.label(
+                egui::RichText::new(format!("📄 {} ({})", file_name, parent_dir))
+                    .color(color_val)
+                    .size(theme.font_size)
+            );
+        }
+        ResultType::Emoji(name, emoji) => {
+            ui.label(
+                egui::RichText::new(format!("{} :{}", emoji, name))
+                    .color(color_val)
+                    .size(theme.font_size)
+            );
+        }
+        ResultType::Currency(from, to, result) => {
+            ui.label(
+                egui::RichText::new(format!("💱 {} {} = {:.2} {} (Live)", query, from, result, to))
+                    .color(color_val)
+                    .size(theme.font_size)
+            );
+        }
+    }
+}
+
+fn execute_result(result: &ResultType, command_config: &CommandConfig) -> Result<(), String> {
+    match result {
+        ResultType::App(app) if app.source == AppSource::Link => open_url(&app.exec_command),
+        ResultType::App(app) => launch_app(app, &app.exec_command, command_config),
+        ResultType::AppAction(app, action) => launch_app(app, &action.exec, command_config),
+        ResultType::Calculator(res) => copy_to_clipboard(res),
+        ResultType::Command(cmd) => execute_command(cmd),
+        ResultType::ShellCommand(cmd, mode) => run_shell_command(cmd, *mode, command_config),
+        ResultType::InlineCommand(_) => Ok(()),
+        ResultType::Output(line) => copy_to_clipboard(&line.text),
+        ResultType::Snippet(_) => Ok(()),
+        ResultType::Plugin(plugin_result) => match plugin_result.actions.first() {
+            Some(action) => execute_plugin_action(action),
+            None => Ok(()),
+        },
+        ResultType::Script(_) => Ok(()),
+        ResultType::Quicklink(link, argument) => {
+            let target = link.expand(argument);
+            match link.kind {
+                QuicklinkKind::Url => open_url(&target),
+                QuicklinkKind::Command => execute_command(&target),
+                QuicklinkKind::Folder => open_file(&expand_home(&target)),
+            }
+        }
+        ResultType::Game(game) => open_url(&format!("steam://rungameid/{}", game.app_id)),
+        ResultType::WebSearch(query) => open_web_search(query),
+        ResultType::Url(url) => open_url(url),
+        ResultType::File(path) => open_file(path),
+        ResultType::Emoji(_, emoji) => copy_to_clipboard(emoji),
+        ResultType::Currency(_, _, result) => copy_to_clipboard(&result.to_string()),
+        ResultType::Suggestion(suggestion) => execute_result(suggestion, command_config),
+    }
+}
+
+fn move_cursor_to_end(ctx: &egui::Context, id: egui::Id, text: &str) {
+    if let Some(mut state) = egui::TextEdit::load_state(ctx, id) {
+        let end = egui::text::CCursor::new(text.chars().count());
+        state.cursor.set_char_range(Some(egui::text::CCursorRange::one(end)));
+        state.store(ctx, id);
+    }
+}
+
+fn link_icon_texture(ctx: &egui::Context, path: &str) -> Option<egui::TextureHandle> {
+    const ICON_EXTENSIONS: [&str; 3] = ["ico", "png", "bmp"];
+    
+    let is_image = std::path::Path::new(path).extension()
+        .and_then(|e| e.to_str())
+        .is_some_and(|e| ICON_EXTENSIONS.iter().any(|icon| e.eq_ignore_ascii_case(icon)));
+    if !is_image {
+        return None;
+    }
+    
+    let id = egui::Id::new(("link_icon", path));
+    if let Some(texture) = ctx.data(|data| data.get_temp::<Option<egui::TextureHandle>>(id)) {
+        return texture;
+    }
+    let texture = match fs::read(path).map_err(|e| e.to_string()).and_then(|data| decode_icon_file(&data)) {
+        Ok(image) => Some(ctx.load_texture(path, image, egui::TextureOptions::LINEAR)),
+        Err(e) => {
+            debug!(path = %path, "skipping shortcut icon: {}", e);
+            None
+        }
+    };
+    ctx.data_mut(|data| data.insert_temp(id, texture.clone()));
+    texture
+}
+
+fn decode_icon_file(data: &[u8]) -> Result<egui::ColorImage, String> {
+    let image = image::load_from_memory(data).map_err(|e| e.to_string())?.to_rgba8();
+    let size = [image.width() as usize, image.height() as usize];
+    Ok(egui::ColorImage::from_rgba_unmultiplied(size, image.as_raw()))
+}
+
+fn render_highlighted_text(
+    ui: &mut egui::Ui,
+    text: &str,
+    match_indices: &[usize],
+    is_selected: bool,
+    theme: &Theme,
+    alpha: f32,
+) {
+    let normal_color = if is_selected {
+        theme.hex_to_rgb(&theme.selection_text)
+    } else {
+        theme.hex_to_rgb(&theme.text_color)
+    };
+    
+    let highlight_color = theme.hex_to_rgb(&theme.highlight_color);
+    
+    ui.horizontal(|ui| {
+        ui.spacing_mut().item_spacing.x = 0.0;
+        
+        for (i, ch) in text.chars().enumerate() {
+            let base_color = if match_indices.contains(&i) {
+                highlight_color
+            } else {
+                normal_color
+            };
+            
+            let color = egui::Color32::from_rgba_premultiplied(
+                (base_color[0] * 255.0 * alpha) as u8,
+                (base_color[1] * 255.0 * alpha) as u8,
+                (base_color[2] * 255.0 * alpha) as u8,
+                (alpha * 255.0) as u8,
+            );
+            
+            ui.label(
+                egui::RichText::new(ch.to_string())
+                    .color(color)
+                    .font(egui::FontId::proportional(theme.font_size))
+            );
+        }
+    });
+}
+
+#[derive(Debug, Deserialize)]
+struct ExchangeRatesResponse {
+    rates: std::collections::HashMap<String, f64>,
+}
+
+fn is_calculation(query: &str) -> bool {
+    let trimmed = query.trim();
+    
+    let has_operator = trimmed.contains('+') || 
+                      trimmed.contains('-') || 
+                      trimmed.contains('*') || 
+                      trimmed.contains('/') ||
+                      trimmed.contains('%') ||
+                      trimmed.contains('^');
+    
+    let has_numbers = trimmed.chars().any(|c| c.is_ascii_digit());
+    
+    let has_letters = trimmed.chars().any(|c| c.is_ascii_alphabetic() && c != 'e' && c != 'E' && c != 'p' && c != 'P' && c != 'i' && c != 'I');
+    
+    let reasonable_length = trimmed.len() >= 2 && trimmed.len() <= 50;
+    
+    has_operator && has_numbers && !has_letters && reasonable_length
+}
+
+fn normalize_currency_code(code: &str) -> Option<String> {
+    let code_lower = code.to_lowercase();
+    let result = match code_lower.as_str() {
+        "usd" | "dollar" | "dollars" => "USD",
+        "eur" | "euro" | "euros" => "EUR", 
+        "gbp" | "pound" | "pounds" | "sterling" => "GBP",
+        "jpy" | "yen" => "JPY",
+        "cad" | "canadian dollar" => "CAD",
+        "aud" | "australian dollar" => "AUD",
+        "chf" | "swiss franc" => "CHF",
+        "cny" | "yuan" | "renminbi" => "CNY",
+        "inr" | "rupee" | "rupees" => "INR",
+        _ if code.len() == 3 => {
+            return Some(code.to_uppercase());
+        }
+        _ => return None,
+    };
+    Some(result.to_string())
+}
+
+fn parse_currency_query(query: &str) -> Option<(f64, String, String)> {
+    let parts: Vec<&str> = query.split_whitespace().collect();
+    if parts.len() < 3 {
+        return None;
+    }
+    
+    let mut amount_str = parts[0];
+    let mut from_currency_str = parts[1];
+    let mut to_currency_str = parts.get(2).copied().unwrap_or("");
+    
+    if parts[0].to_lowercase() == "convert" && parts.len() >= 4 {
+        amount_str = parts[1];
+        from_currency_str = parts[2];
+        to_currency_str = parts.get(3).copied().unwrap_or("");
+    }
+    
+    if parts.len() >= 4 && parts[2].to_lowercase() == "to" {
+        to_currency_str = parts[3];
+    } else if parts.len() >= 4 && parts[0].to_lowercase() == "convert" && parts[3].to_lowercase() == "to" {
+        to_currency_str = parts.get(4).copied().unwrap_or("");
+    }
+    
+    if to_currency_str.is_empty() {
+        return None;
+    }
+    
+    Some((
+        amount_str.parse::<f64>().ok()?,
+        normalize_currency_code(from_currency_str)?,
+        normalize_currency_code(to_currency_str)?,
+    ))
+}
+
+async fn convert_currency_online(amount: f64, from_currency: &str, to_currency: &str, client: &reqwest::Client) -> Option<(String, String, f64)> {
+    if from_currency == to_currency {
+        return Some((from_currency.to_string(), to_currency.to_string(), amount));
+    }
+    
+    let url = format!("https://api.exchangerate-api.com/v4/latest/{}", from_currency);
+    
+    match client.get(&url).send().await {
+        Ok(response) => {
+            if !response.status().is_success() {
+                warn!(url = %url, status = %response.status(), "exchange rate request failed");
+            }
+            if response.status().is_success() {
+                if let Ok(exchange_data) = response.json::<ExchangeRatesResponse>().await {
+                    if let Some(rate) = exchange_data.rates.get(to_currency) {
+                        let converted = amount * rate;
+                        return Some((from_currency.to_string(), to_currency.to_string(), converted));
+                    }
+                }
+            }
+        }
+        Err(e) => {
+            warn!(url = %url, "exchange rate request failed: {}", e);
+            let fallback_url = format!("https://api.frankfurter.app/latest?from={}", from_currency);
+            let fallback = client.get(&fallback_url).send().await;
+            if let Err(e) = &fallback {
+                warn!(url = %fallback_url, "exchange rate request failed: {}", e);
+            }
+            if let Ok(fallback_response) = fallback {
+                if fallback_response.status().is_success() {
+                    if let Ok(exchange_data) = fallback_response.json::<ExchangeRatesResponse>().await {
+                        if let Some(rate) = exchange_data.rates.get(to_currency) {
+                            let converted = amount * rate;
+                            return Some((from_currency.to_string(), to_currency.to_string(), converted));
+                        }
+                    }
+                }
+            }
+            return None;
+        }
+    }
+    None
+}
+
+#[cfg(target_os = "windows")]
+fn copy_to_clipboard(text: &str) -> Result<(), String> {
+    use std::os::windows::process::CommandExt;
+    
+    let mut child = Command::new("clip")
+        .stdin(std::process::Stdio::piped())
+        .creation_flags(CREATE_NO_WINDOW)
+        .spawn()
+        .map_err(|e| format!("Failed to run clip: {}", e))?;
+    
+    if let Some(stdin) = child.stdin.as_mut() {
+        let utf16: Vec<u8> = [0xFF, 0xFE].into_iter()
+            .chain(text.encode_utf16().flat_map(|unit| unit.to_le_bytes()))
+            .collect();
+        stdin.write_all(&utf16).map_err(|e| format!("Failed to copy to clipboard: {}", e))?;
+    }
+    Ok(())
+}
+
+#[cfg(not(target_os = "windows"))]
+fn copy_to_clipboard(text: &str) -> Result<(), String> {
+    let mut child = Command::new("xclip")
+        .arg("-selection")
+        .arg("clipboard")
+        .arg("-i")
+        .stdin(std::process::Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("Failed to run xclip: {}", e))?;
+    
+    if let Some(stdin) = child.stdin.as_mut() {
+        stdin.write_all(text.as_bytes()).map_err(|e| format!("Failed to copy to clipboard: {}", e))?;
+    }
+    Ok(())
+}
+
+#[cfg(target_os = "windows")]
+fn read_clipboard() -> String {
+    use std::os::windows::process::CommandExt;
+    
+    Command::new("powershell")
+        .args(["-NoProfile", "-Command", "Get-Clipboard -Raw"])
+        .creation_flags(CREATE_NO_WINDOW)
+        .output()
+        .map(|output| String::from_utf8_lossy(&output.stdout).trim_end_matches(['\r', '\n']).to_string())
+        .unwrap_or_default()
+}
+
+#[cfg(not(target_os = "windows"))]
+fn read_clipboard() -> String {
+    Command::new("xclip")
+        .args(["-selection", "clipboard", "-o"])
+        .output()
+        .map(|output| String::from_utf8_lossy(&output.stdout).to_string())
+        .unwrap_or_default()
+}
+
+#[cfg(target_os = "windows")]
+fn paste_to_previous_window(delay_ms: u64) -> Result<(), String> {
+    use std::os::windows::process::CommandExt;
+    
+    let script = format!(
+        "Start-Sleep -Milliseconds {}; (New-Object -ComObject WScript.Shell).SendKeys('^v')",
+        delay_ms
+    );
+    Command::new("powershell")
+        .args(["-NoProfile", "-Command", &script])
+        .creation_flags(CREATE_NO_WINDOW)
+        .spawn()
+        .map(|_| ())
+        .map_err(|e| format!("Failed to paste: {}", e))
+}
+
+#[cfg(not(target_os = "windows"))]
+fn paste_to_previous_window(delay_ms: u64) -> Result<(), String> {
+    Command::new("sh")
+        .arg("-c")
+        .arg(format!("sleep {}; xdotool key --clearmodifiers ctrl+v", delay_ms as f64 / 1000.0))
+        .spawn()
+        .map(|_| ())
+        .map_err(|e| format!("Failed to paste: {}", e))
+}
+
+#[cfg(target_os = "windows")]
+fn execute_command(cmd: &str) -> Result<(), String> {
+    info!(command = %cmd, "running command");
+    Command::new("cmd")
+        .args(["/C", "start", "cmd", "/C", cmd])
+        .spawn()
+        .map(|_| ())
+        .map_err(|e| format!("Failed to run '{}': {}", cmd, e))
+}
+
+#[cfg(not(target_os = "windows"))]
+fn execute_command(cmd: &str) -> Result<(), String> {
+    info!(command = %cmd, "running command");
+    Command::new("sh")
+        .arg("-c")
+        .arg(cmd)
+        .spawn()
+        .map(|_| ())
+        .map_err(|e| format!("Failed to run '{}': {}", cmd, e))
+}
+
+#[cfg(target_os = "windows")]
+fn run_shell_command(cmd: &str, mode: RunMode, config: &CommandConfig) -> Result<(), String> {
+    use std::os::windows::process::CommandExt;
+    
+    info!(command = %cmd, mode = mode.as_str(), "running shell command");
+    let spawned = match mode {
+        RunMode::Background => Command::new("cmd")
+            .args(["/C", cmd])
+            .creation_flags(CREATE_NO_WINDOW)
+            .spawn(),
+        RunMode::Terminal => Command::new("cmd")
+            .args(["/C", "start", ""])
+            .args(config.terminal_command(vec![cmd.to_string()]))
+            .spawn(),
+        RunMode::LoginShell => match std::env::var("SHELL") {
+            Ok(shell) => Command::new(shell)
+                .args(["-l", "-c", cmd])
+                .creation_flags(CREATE_NO_WINDOW)
+                .spawn(),
+            Err(_) => Command::new("powershell")
+                .args(["-NoLogo", "-Command", cmd])
+                .creation_flags(CREATE_NO_WINDOW)
+                .spawn(),
+        },
+    };
+    
+    spawned
+        .map(|_| ())
+        .map_err(|e| format!("Failed to run '{}': {}", cmd, e))
+}
+
+#[cfg(not(target_os = "windows"))]
+fn run_shell_command(cmd: &str, mode: RunMode, config: &CommandConfig) -> Result<(), String> {
+    info!(command = %cmd, mode = mode.as_str(), "running shell command");
+    let shell = std::env::var("SHELL").unwrap_or_else(|_| "sh".to_string());
+    
+    let spawned = match mode {
+        RunMode::Background => return execute_command(cmd),
+        RunMode::Terminal => {
+            let argv = config.terminal_command(vec![
+                "sh".to_string(),
+                "-c".to_string(),
+                format!("{}; exec \"{}\"", cmd, shell),
+            ]);
+            Command::new(&argv[0])
+                .args(&argv[1..])
+                .spawn()
+                .map_err(|e| format!("Failed to start terminal '{}': {}", argv[0], e))
+        }
+        RunMode::LoginShell => Command::new(&shell)
+            .arg("-l")
+            .arg("-c")
+            .arg(cmd)
+            .spawn()
+            .map_err(|e| format!("Failed to run '{}' in {}: {}", cmd, shell, e)),
+    };
+    
+    spawned.map(|_| ())
+}
+
+async fn capture_command_output(cmd: &str, timeout: Duration) -> Vec<OutputLine> {
+    let mut command = inline_shell_command(cmd);
+    command.stdin(std::process::Stdio::null())
+        .stdout(std::process::Stdio::piped())
+        .stderr(std::process::Stdio::piped())
+        .kill_on_drop(true);
+    #[cfg(not(target_os = "windows"))]
+    command.process_group(0);
+    
+    let child = match command.spawn() {
+        Ok(child) => child,
+        Err(e) => return vec![OutputLine::status(format!("✗ Failed to run command: {}", e))],
+    };
+    let pid = child.id();
+    
+    match tokio::time::timeout(timeout, child.wait_with_output()).await {
+        Ok(Ok(output)) => {
+            let stdout = String::from_utf8_lossy(&output.stdout);
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            let mut lines: Vec<OutputLine> = stdout.lines()
+                .map(|text| OutputLine { text: text.to_string(), stream: OutputStream::Stdout })
+                .chain(stderr.lines().map(|text| OutputLine { text: text.to_string(), stream: OutputStream::Stderr }))
+                .take(1000)
+                .collect();
+            
+            let status = match output.status.code() {
+                Some(0) => format!("✓ exit 0 · {} lines", lines.len()),
+                Some(code) => format!("✗ exit {} · {} lines", code, lines.len()),
+                None => format!("✗ terminated · {} lines", lines.len()),
+            };
+            lines.insert(0, OutputLine::status(status));
+            lines
+        }
+        Ok(Err(e)) => vec![OutputLine::status(format!("✗ Failed to run command: {}", e))],
+        Err(_) => {
+            if let Some(pid) = pid {
+                kill_process_tree(pid);
+            }
+            vec![OutputLine::status(format!("⏱ Timed out after {}s", timeout.as_secs()))]
+        }
+    }
+}
+
+#[cfg(target_os = "windows")]
+fn kill_process_tree(pid: u32) {
+    use std::os::windows::process::CommandExt;
+    
+    let killed = Command::new("taskkill")
+        .args(["/T", "/F", "/PID", &pid.to_string()])
+        .creation_flags(CREATE_NO_WINDOW)
+        .stdout(std::process::Stdio::null())
+        .stderr(std::process::Stdio::null())
+        .status();
+    if !killed.is_ok_and(|status| status.success()) {
+        warn!(pid, "failed to kill timed out command");
+    }
+}
+
+#[cfg(not(target_os = "windows"))]
+fn kill_process_tree(pid: u32) {
+    // SAFETY: killpg(2) only signals the process group the command was spawned into.
+    if unsafe { libc::killpg(pid as libc::pid_t, libc::SIGKILL) } == -1 {
+        warn!(pid, "failed to kill timed out command: {}", std::io::Error::last_os_error());
+    }
+}
+
+#[cfg(target_os = "windows")]
+fn inline_shell_command(cmd: &str) -> tokio::process::Command {
+    let mut command = tokio::process::Command::new("cmd");
+    command.args(["/C", cmd]).creation_flags(CREATE_NO_WINDOW);
+    command
+}
+
+#[cfg(not(target_os = "windows"))]
+fn inline_shell_command(cmd: &str) -> tokio::process::Command {
+    let mut command = tokio::process::Command::new("sh");
+    command.arg("-c").arg(cmd);
+    command
+}
+
+fn get_plugins_dir() -> PathBuf {
+    get_config_dir().join("plugins")
+}
+
+fn load_plugins() -> Vec<PluginManifest> {
+    let states = load_plugin_states();
+    
+    let mut plugins: Vec<PluginManifest> = fs::read_dir(get_plugins_dir())
+        .map(|entries| {
+            entries.flatten()
+                .map(|entry| entry.path())
+                .filter(|path| path.is_dir())
+                .filter(|path| !path.file_name().and_then(|n| n.to_str()).is_some_and(|name| name.starts_with('.')))
+                .filter_map(|dir| {
+                    let content = fs::read_to_string(dir.join("plugin.json")).ok()?;
+                    let mut manifest: PluginManifest = match serde_json::from_str(&content) {
+                        Ok(manifest) => manifest,
+                        Err(e) => {
+                            warn!(dir = %dir.display(), "invalid plugin.json: {}", e);
+                            return None;
+                        }
+                    };
+                    manifest.values = fs::read_to_string(dir.join("settings.json"))
+                        .ok()
+                        .and_then(|content| serde_json::from_str(&content).ok())
+                        .unwrap_or_default();
+                    manifest.dir = dir;
+                    manifest.enabled = states.get(&manifest.id()).copied().unwrap_or(true);
+                    Some(manifest)
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    
+    plugins.sort_by(|a, b| a.name.cmp(&b.name));
+    debug!(plugins = plugins.len(), "loaded plugins");
+    plugins
+}
+
+fn find_plugin<'a>(plugins: &'a [PluginManifest], query: &str) -> Option<(&'a PluginManifest, String)> {
+    let (trigger, plugin_query) = query.split_once(' ').unwrap_or((query, ""));
+    plugins.iter()
+        .filter(|plugin| plugin.enabled && check_plugin_compatibility(plugin).is_ok())
+        .find(|plugin| !plugin.trigger.is_empty() && plugin.trigger == trigger)
+        .map(|plugin| (plugin, plugin_query.trim().to_string()))
+}
+
+async fn run_plugin(plugin: &PluginManifest, query: &str) -> Result<Vec<PluginResult>, String> {
+    use tokio::io::AsyncWriteExt;
+    
+    let local_executable = plugin.dir.join(&plugin.executable);
+    let mut command = if local_executable.is_file() {
+        tokio::process::Command::new(local_executable)
+    } else {
+        tokio::process::Command::new(&plugin.executable)
+    };
+    command.args(&plugin.args)
+        .current_dir(&plugin.dir)
+        .stdin(std::process::Stdio::piped())
+        .stdout(std::process::Stdio::piped())
+        .stderr(std::process::Stdio::piped())
+        .kill_on_drop(true);
+    
+    #[cfg(target_os = "windows")]
+    command.creation_flags(CREATE_NO_WINDOW);
+    
+    let mut child = command.spawn().map_err(|e| format!("Failed to start plugin: {}", e))?;
+    let request = serde_json::json!({
+        "query": query,
+        "trigger": plugin.trigger,
+        "settings": plugin_settings_with_defaults(plugin),
+    })
+    .to_string();
+    
+    let output = tokio::time::timeout(Duration::from_millis(plugin.timeout_ms), async move {
+        if let Some(mut stdin) = child.stdin.take() {
+            let _ = stdin.write_all(request.as_bytes()).await;
+        }
+        child.wait_with_output().await
+    })
+    .await
+    .map_err(|_| format!("Timed out after {}ms", plugin.timeout_ms))?
+    .map_err(|e| format!("Failed to read plugin output: {}", e))?;
+    
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(format!(
+            "Exited with {}: {}",
+            output.status,
+            stderr.lines().next().unwrap_or("").trim()
+        ));
+    }
+    
+    serde_json::from_slice(&output.stdout).map_err(|e| format!("Invalid plugin output: {}", e))
+}
+
+fn load_plugin_states() -> std::collections::HashMap<String, bool> {
+    fs::read_to_string(get_config_dir().join("plugins.conf"))
+        .map(|content| {
+            content.lines()
+                .filter(|line| !line.starts_with('#'))
+                .filter_map(|line| line.split_once('='))
+                .map(|(id, enabled)| (id.trim().to_string(), enabled.trim() == "true"))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn save_plugin_states(plugins: &[PluginManifest]) {
+    let config_dir = get_config_dir();
+    let _ = fs::create_dir_all(&config_dir);
+    
+    let mut content = "# Flint Launcher Plugin States\n# Format: plugin_folder=true|false\n\n".to_string();
+    for plugin in plugins {
+        content.push_str(&format!("{}={}\n", plugin.id(), plugin.enabled));
+    }
+    
+    let _ = fs::write(config_dir.join("plugins.conf"), content);
+}
+
+fn plugin_settings_with_defaults(plugin: &PluginManifest) -> serde_json::Map<String, serde_json::Value> {
+    let mut values = plugin.values.clone();
+    for setting in &plugin.settings {
+        values.entry(setting.key.clone()).or_insert_with(|| setting.default.clone());
+    }
+    values
+}
+
+fn save_plugin_settings(plugin: &PluginManifest) -> Result<(), String> {
+    let content = serde_json::to_string_pretty(&plugin.values)
+        .map_err(|e| format!("Failed to encode settings: {}", e))?;
+    fs::write(plugin.dir.join("settings.json"), content)
+        .map_err(|e| format!("Failed to save settings for {}: {}", plugin.name, e))
+}
+
+fn render_plugin_setting(ui: &mut egui::Ui, setting: &PluginSetting, value: &mut serde_json::Value) {
+    let label = if setting.label.is_empty() { &setting.key } else { &setting.label };
+    
+    match setting.kind.as_str() {
+        "bool" => {
+            let mut checked = value.as_bool().unwrap_or(false);
+            if ui.checkbox(&mut checked, label).changed() {
+                *value = serde_json::Value::Bool(checked);
+            }
+        }
+        "number" => {
+            let mut number = value.as_f64().unwrap_or(0.0);
+            ui.horizontal(|ui| {
+                ui.label(label);
+                if ui.add(egui::DragValue::new(&mut number)).changed() {
+                    *value = serde_json::json!(number);
+                }
+            });
+        }
+        _ => {
+            let mut text = value.as_str().unwrap_or("").to_string();
+            ui.horizontal(|ui| {
+                ui.label(label);
+                if ui.text_edit_singleline(&mut text).changed() {
+                    *value = serde_json::Value::String(text);
+                }
+            });
+        }
+    }
+}
+
+fn parse_version(version: &str) -> Vec<u64> {
+    version.trim()
+        .trim_start_matches(">=")
+        .trim_start_matches('v')
+        .split('.')
+        .map(|part| part.trim().parse().unwrap_or(0))
+        .collect()
+}
+
+fn check_plugin_compatibility(plugin: &PluginManifest) -> Result<(), String> {
+    if plugin.flint_version.trim().is_empty() {
+        return Ok(());
+    }
+    
+    let required = parse_version(&plugin.flint_version);
+    let current = parse_version(env!("CARGO_PKG_VERSION"));
+    if required.first() != current.first() || required > current {
+        return Err(format!(
+            "{} requires Flint {} (running {})",
+            plugin.name,
+            plugin.flint_version,
+            env!("CARGO_PKG_VERSION")
+        ));
+    }
+    
+    Ok(())
+}
+
+fn install_plugin_archive(archive_path: &std::path::Path) -> Result<String, String> {
+    install_plugin_archive_into(archive_path, &get_plugins_dir())
+}
+
+fn install_plugin_archive_into(archive_path: &std::path::Path, plugins_dir: &std::path::Path) -> Result<String, String> {
+    use std::io::Read;
+    
+    let file = File::open(archive_path)
+        .map_err(|e| format!("Failed to open {}: {}", archive_path.display(), e))?;
+    let mut archive = zip::ZipArchive::new(file).map_err(|e| format!("Invalid plugin archive: {}", e))?;
+    
+    let manifest_path = (0..archive.len())
+        .filter_map(|i| archive.by_index(i).ok().and_then(|entry| entry.enclosed_name().map(|p| p.to_path_buf())))
+        .filter(|path| path.file_name().and_then(|n| n.to_str()) == Some("plugin.json"))
+        .min_by_key(|path| path.components().count())
+        .ok_or_else(|| "Archive does not contain a plugin.json manifest".to_string())?;
+    let root = manifest_path.parent().map(|p| p.to_path_buf()).unwrap_or_default();
+    
+    let mut content = String::new();
+    archive.by_name(&manifest_path.to_string_lossy())
+        .map_err(|e| format!("Failed to read plugin.json: {}", e))?
+        .read_to_string(&mut content)
+        .map_err(|e| format!("Failed to read plugin.json: {}", e))?;
+    let manifest: PluginManifest = serde_json::from_str(&content)
+        .map_err(|e| format!("Invalid plugin.json: {}", e))?;
+    check_plugin_compatibility(&manifest)?;
+    
+    let folder_name = plugin_folder_name(&manifest.name, archive_path);
+    let target = plugins_dir.join(&folder_name);
+    if target == plugins_dir || target.parent() != Some(plugins_dir) {
+        return Err(format!("Refusing to install plugin \"{}\" into {}", manifest.name, target.display()));
+    }
+    fs::create_dir_all(plugins_dir).map_err(|e| format!("Failed to create {}: {}", plugins_dir.display(), e))?;
+    
+    let staging = plugins_dir.join(format!(".{}.installing", folder_name));
+    let _ = fs::remove_dir_all(&staging);
+    let installed = extract_plugin_archive(&mut archive, &root, &staging)
+        .and_then(|()| {
+            let content = fs::read_to_string(staging.join("plugin.json"))
+                .map_err(|e| format!("Failed to read plugin.json: {}", e))?;
+            let staged: PluginManifest = serde_json::from_str(&content)
+                .map_err(|e| format!("Invalid plugin.json: {}", e))?;
+            check_plugin_compatibility(&staged)?;
+            
+            if let Ok(settings) = fs::read(target.join("settings.json")) {
+                let _ = fs::write(staging.join("settings.json"), settings);
+            }
+            replace_plugin_dir(&staging, &target, &plugins_dir.join(format!(".{}.previous", folder_name)))
+        });
+    if installed.is_err() {
+        let _ = fs::remove_dir_all(&staging);
+    }
+    installed?;
+    
+    info!(plugin = %manifest.name, dir = %target.display(), "installed plugin");
+    Ok(manifest.name)
+}
+
+fn extract_plugin_archive(
+    archive: &mut zip::ZipArchive<File>,
+    root: &std::path::Path,
+    destination: &std::path::Path,
+) -> Result<(), String> {
+    fs::create_dir_all(destination).map_err(|e| format!("Failed to create {}: {}", destination.display(), e))?;
+    
+    for i in 0..archive.len() {
+        let mut entry = archive.by_index(i).map_err(|e| format!("Invalid plugin archive: {}", e))?;
+        let relative = match entry.enclosed_name().and_then(|path| path.strip_prefix(root).ok()) {
+            Some(relative) if !relative.as_os_str().is_empty() => relative.to_path_buf(),
+            _ => continue,
+        };
+        
+        let out_path = destination.join(relative);
+        if entry.is_dir() {
+            let _ = fs::create_dir_all(&out_path);
+            continue;
+        }
+        if let Some(parent) = out_path.parent() {
+            let _ = fs::create_dir_all(parent);
+        }
+        
+        let mut out_file = File::create(&out_path)
+            .map_err(|e| format!("Failed to write {}: {}", out_path.display(), e))?;
+        std::io::copy(&mut entry, &mut out_file)
+            .map_err(|e| format!("Failed to extract {}: {}", out_path.display(), e))?;
+        
+        #[cfg(not(target_os = "windows"))]
+        if let Some(mode) = entry.unix_mode() {
+            use std::os::unix::fs::PermissionsExt;
+            let _ = fs::set_permissions(&out_path, fs::Permissions::from_mode(mode));
+        }
+    }
+    
+    Ok(())
+}
+
+fn replace_plugin_dir(staging: &std::path::Path, target: &std::path::Path, backup: &std::path::Path) -> Result<(), String> {
+    if !target.exists() {
+        return fs::rename(staging, target).map_err(|e| format!("Failed to install {}: {}", target.display(), e));
+    }
+    
+    let _ = fs::remove_dir_all(backup);
+    fs::rename(target, backup).map_err(|e| format!("Failed to replace {}: {}", target.display(), e))?;
+    if let Err(e) = fs::rename(staging, target) {
+        let _ = fs::rename(backup, target);
+        return Err(format!("Failed to replace {}: {}", target.display(), e));
+    }
+    if let Err(e) = fs::remove_dir_all(backup) {
+        warn!(dir = %backup.display(), "failed to remove previous plugin version: {}", e);
+    }
+    Ok(())
+}
+
+fn plugin_folder_name(name: &str, archive_path: &std::path::Path) -> String {
+    let sanitize = |text: &str| -> String {
+        let folder_name: String = text.to_lowercase()
+            .chars()
+            .map(|c| if c.is_ascii_alphanumeric() { c } else { '-' })
+            .collect();
+        folder_name.trim_matches('-').to_string()
+    };
+    
+    [name, archive_path.file_stem().and_then(|stem| stem.to_str()).unwrap_or_default()].into_iter()
+        .map(sanitize)
+        .find(|folder_name| !folder_name.is_empty())
+        .unwrap_or_else(|| format!("plugin-{}", uuid::Uuid::new_v4()))
+}
+
+fn execute_plugin_action(action: &PluginAction) -> Result<(), String> {
+    match action.kind.as_str() {
+        "open_url" => open_url(&action.value),
+        "open_file" => open_file(&expand_home(&action.value)),
+        "run" => execute_command(&action.value),
+        "copy" => copy_to_clipboard(&action.value),
+        other => Err(format!("Unknown plugin action: {}", other)),
+    }
+}
+
+fn list_lua_scripts() -> Vec<PathBuf> {
+    let mut scripts: Vec<PathBuf> = fs::read_dir(get_config_dir())
+        .map(|entries| {
+            entries.flatten()
+                .map(|entry| entry.path())
+                .filter(|path| path.is_file() && path.extension().and_then(|e| e.to_str()) == Some("lua"))
+                .collect()
+        })
+        .unwrap_or_default();
+    
+    scripts.sort();
+    scripts
+}
+
+const LUA_REMOVED_GLOBALS: [&str; 3] = ["load", "loadfile", "dofile"];
+
+fn new_script_lua() -> mlua::Result<mlua::Lua> {
+    let libs = mlua::StdLib::TABLE | mlua::StdLib::STRING | mlua::StdLib::MATH | mlua::StdLib::UTF8;
+    let lua = mlua::Lua::new_with(libs, mlua::LuaOptions::new())?;
+    for name in LUA_REMOVED_GLOBALS {
+        lua.globals().raw_remove(name)?;
+    }
+    Ok(lua)
+}
+
+fn install_lua_api(
+    lua: &mlua::Lua,
+    client: reqwest::Client,
+    handle: tokio::runtime::Handle,
+    http: Arc<Mutex<LuaHttpCache>>,
+) -> mlua::Result<()> {
+    lua.set_named_registry_value("flint_providers", lua.create_table()?)?;
+    lua.set_named_registry_value("flint_actions", lua.create_table()?)?;
+    
+    let flint = lua.create_table()?;
+    
+    flint.set("register", lua.create_function(|lua, (trigger, provider): (String, mlua::Function)| {
+        let providers: mlua::Table = lua.named_registry_value("flint_providers")?;
+        providers.set(trigger, provider)
+    })?)?;
+    
+    flint.set("open_url", lua.create_function(|_, url: String| {
+        open_url(&url).map_err(mlua::Error::RuntimeError)
+    })?)?;
+    
+    flint.set("copy_to_clipboard", lua.create_function(|_, text: String| {
+        copy_to_clipboard(&text).map_err(mlua::Error::RuntimeError)
+    })?)?;
+    
+    flint.set("execute_command", lua.create_function(|_, cmd: String| {
+        execute_command(&cmd).map_err(mlua::Error::RuntimeError)
+    })?)?;
+    
+    flint.set("http_get", lua.create_function(move |_, url: String| {
+        if !url.starts_with("http://") && !url.starts_with("https://") {
+            return Err(mlua::Error::RuntimeError(format!("http_get only supports http(s) URLs: {}", url)));
+        }
+        
+        let mut cache = http.lock().map_err(|_| mlua::Error::RuntimeError("http_get cache is unavailable".to_string()))?;
+        if let Some((fetched, response)) = cache.responses.get(&url) {
+            match response {
+                None => return Ok(None),
+                Some(_) if fetched.elapsed() >= LUA_HTTP_CACHE_TTL => {}
+                Some(Ok(body)) => return Ok(Some(body.clone())),
+                Some(Err(e)) => return Err(mlua::Error::RuntimeError(e.clone())),
+            }
+        }
+        
+        if cache.responses.len() >= LUA_HTTP_CACHE_MAX_ENTRIES {
+            cache.responses.retain(|_, (_, response)| response.is_none());
+        }
+        cache.responses.insert(url.clone(), (Instant::now(), None));
+        drop(cache);
+        
+        let client = client.clone();
+        let http = http.clone();
+        handle.spawn(async move {
+            let response = async {
+                client.get(&url).send().await?.error_for_status()?.text().await
+            }
+            .await
+            .map_err(|e| {
+                warn!(url = %url, "Lua http_get failed: {}", e);
+                format!("http_get {} failed: {}", url, e)
+            });
+            
+            let egui_ctx = http.lock().ok().and_then(|mut cache| {
+                cache.responses.insert(url, (Instant::now(), Some(response)));
+                cache.generation += 1;
+                cache.egui_ctx.clone()
+            });
+            if let Some(ctx) = egui_ctx {
+                ctx.request_repaint();
+            }
+        });
+        Ok(None)
+    })?)?;
+    
+    lua.globals().set("flint", flint)
+}
+
+fn lua_table_to_result(item: mlua::Table, actions: &mlua::Table) -> mlua::Result<Option<ResultType>> {
+    let text = |key: &str| -> mlua::Result<String> {
+        Ok(item.get::<_, Option<String>>(key)?.unwrap_or_default())
+    };
+    
+    let result = match text("type")?.as_str() {
+        "app" => ResultType::App(AppEntry {
+            name: text("name")?,
+            generic_name: text("generic_name")?,
+            keywords: Vec::new(),
+            categories: Vec::new(),
+            comment: String::new(),
+            desktop_id: text("name")?,
+            exec_command: text("exec")?,
+            match_indices: Vec::new(),
+            source: AppSource::Native,
+            actions: Vec::new(),
+            terminal: item.get::<_, Option<bool>>("terminal")?.unwrap_or(false),
+            icon: text("icon")?,
+            working_dir: item.get::<_, Option<String>>("path")?.map(|path| expand_home(&path)),
+            desktop_file: None,
+        }),
+        "calculator" => ResultType::Calculator(text("value")?),
+        "command" => ResultType::Command(text("command")?),
+        "web_search" => ResultType::WebSearch(text("query")?),
+        "url" => ResultType::Url(text("url")?),
+        "file" => ResultType::File(expand_home(&text("path")?)),
+        "emoji" => ResultType::Emoji(text("name")?, text("emoji")?),
+        "" | "script" => {
+            let action = match item.get::<_, Option<mlua::Function>>("action")? {
+                Some(function) => {
+                    actions.push(function)?;
+                    Some(actions.raw_len())
+                }
+                None => None,
+            };
+            ResultType::Script(ScriptResult {
+                title: text("title")?,
+                subtitle: text("subtitle")?,
+                action,
+            })
+        }
+        _ => return Ok(None),
+    };
+    
+    Ok(Some(result))
+}
+
+fn scan_path_executables() -> Vec<String> {
+    let mut names = Vec::new();
+    
+    if let Some(path_var) = std::env::var_os("PATH") {
+        for dir in std::env::split_paths(&path_var) {
+            if let Ok(entries) = fs::read_dir(&dir) {
+                for entry in entries.flatten() {
+                    if let Some(name) = executable_name(&entry.path()) {
+                        names.push(name);
+                    }
+                }
+            }
+        }
+    }
+    
+    names.sort();
+    names.dedup();
+    names
+}
+
+#[cfg(target_os = "windows")]
+fn executable_name(path: &std::path::Path) -> Option<String> {
+    let path_ext = std::env::var("PATHEXT").unwrap_or_else(|_| ".COM;.EXE;.BAT;.CMD".to_string());
+    let ext = path.extension()?.to_str()?;
+    if !path_ext.split(';').any(|e| e.trim_start_matches('.').eq_ignore_ascii_case(ext)) {
+        return None;
+    }
+    path.file_stem().and_then(|s| s.to_str()).map(|s| s.to_string())
+}
+
+#[cfg(not(target_os = "windows"))]
+fn executable_name(path: &std::path::Path) -> Option<String> {
+    use std::os::unix::fs::PermissionsExt;
+    
+    let metadata = fs::metadata(path).ok()?;
+    if !metadata.is_file() || metadata.permissions().mode() & 0o111 == 0 {
+        return None;
+    }
+    path.file_name().and_then(|s| s.to_str()).map(|s| s.to_string())
+}
+
+#[cfg(target_os = "windows")]
+fn open_web_search(query: &str) -> Result<(), String> {
+    let url = format!("https://duckduckgo.com/?q={}", urlencoding::encode(query));
+    open_url(&url)
+}
+
+#[cfg(not(target_os = "windows"))]
+fn open_web_search(query: &str) -> Result<(), String> {
+    let url = format!("https://duckduckgo.com/?q={}", urlencoding::encode(query));
+    open_url(&url)
+}
+
+#[cfg(target_os = "windows")]
+fn open_url(url: &str) -> Result<(), String> {
+    info!(url = %url, "opening URL");
+    Command::new("explorer")
+        .arg(url)
+        .spawn()
+        .map(|_| ())
+        .map_err(|e| format!("Failed to open {}: {}", url, e))
+}
+
+#[cfg(not(target_os = "windows"))]
+fn open_url(url: &str) -> Result<(), String> {
+    info!(url = %url, "opening URL");
+    Command::new("xdg-open")
+        .arg(url)
+        .spawn()
+        .map(|_| ())
+        .map_err(|e| format!("Failed to open {}: {}", url, e))
+}
+
+#[cfg(target_os = "windows")]
+fn open_file(path: &std::path::Path) -> Result<(), String> {
+    info!(path = %path.display(), "opening file");
+    if !path.exists() {
+        return Err(format!("{} does not exist", path.display()));
+    }
+    Command::new("cmd")
+        .args(["/C", "start", "", &path.to_string_lossy()])
+        .spawn()
+        .map(|_| ())
+        .map_err(|e| format!("Failed to open {}: {}", path.display(), e))
+}
+
+#[cfg(not(target_os = "windows"))]
+fn open_file(path: &std::path::Path) -> Result<(), String> {
+    info!(path = %path.display(), "opening file");
+    if !path.exists() {
+        return Err(format!("{} does not exist", path.display()));
+    }
+    Command::new("xdg-open")
+        .arg(path)
+        .spawn()
+        .map(|_| ())
+        .map_err(|e| format!("Failed to open {}: {}", path.display(), e))
+}
+
+fn search_files(query: &str, transliterate: bool) -> Vec<PathBuf> {
+    let mut results = Vec::new();
+    let query_lower = fold_text(query, transliterate);
+    
+    let search_dirs = [
+        dirs::download_dir(),
+        dirs::document_dir(),
+        dirs::desktop_dir(),
+        dirs::picture_dir(),
+        dirs::audio_dir(),
+        dirs::video_dir(),
+    ];
+    
+    for dir in search_dirs.iter().flatten() {
+        if let Ok(entries) = fs::read_dir(dir) {
+            for entry in entries.flatten() {
+                let path = entry.path();
+                if let Some(file_name) = path.file_name().and_then(|n| n.to_str()) {
+                    if fold_text(file_name, transliterate).contains(&query_lower) {
+                        results.push(path);
+                        if results.len() >= 5 {
+                            break;
+                        }
+                    }
+                }
+            }
+        }
+    }
+    
+    results.sort_by(|a, b| {
+        let a_name = a.file_name().and_then(|n| n.to_str()).unwrap_or("");
+        let b_name = b.file_name().and_then(|n| n.to_str()).unwrap_or("");
+        a_name.len().cmp(&b_name.len())
+    });
+    
+    results.into_iter().take(8).collect()
+}
+
+fn get_snippets_dir() -> PathBuf {
+    get_config_dir().join("snippets")
+}
+
+fn load_snippets() -> Vec<Snippet> {
+    let snippets_dir = get_snippets_dir();
+    if !snippets_dir.exists() {
+        create_default_snippets(&snippets_dir);
+    }
+    
+    let mut snippets: Vec<Snippet> = fs::read_dir(&snippets_dir)
+        .map(|entries| {
+            entries.flatten()
+                .map(|entry| entry.path())
+                .filter(|path| path.is_file())
+                .filter_map(|path| {
+                    let name = path.file_stem()?.to_str()?.to_string();
+                    let body = fs::read_to_string(&path).ok()?;
+                    Some(Snippet { name, body: body.trim_end_matches(['\r', '\n']).to_string() })
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    
+    snippets.sort_by(|a, b| a.name.cmp(&b.name));
+    snippets
+}
+
+fn snippet_inputs(body: &str) -> Vec<String> {
+    let mut inputs = Vec::new();
+    let mut rest = body;
+    
+    while let Some(start) = rest.find("{input:") {
+        let after = &rest[start + 7..];
+        match after.find('}') {
+            Some(end) => {
+                let name = after[..end].trim().to_string();
+                if !name.is_empty() && !inputs.contains(&name) {
+                    inputs.push(name);
+                }
+                rest = &after[end + 1..];
+            }
+            None => break,
+        }
+    }
+    
+    inputs
+}
+
+fn expand_snippet(body: &str, inputs: &[(String, String)], config: &SnippetConfig) -> String {
+    let mut expanded = String::with_capacity(body.len());
+    let mut rest = body;
+    
+    while let Some(end) = rest.find('}') {
+        let Some(start) = rest[..end].rfind('{') else {
+            expanded.push_str(&rest[..=end]);
+            rest = &rest[end + 1..];
+            continue;
+        };
+        
+        expanded.push_str(&rest[..start]);
+        let token = &rest[start + 1..end];
+        match expand_placeholder(token, inputs, config) {
+            Some(value) => expanded.push_str(&value),
+            None => expanded.push_str(&rest[start..=end]),
+        }
+        rest = &rest[end + 1..];
+    }
+    
+    expanded.push_str(rest);
+    expanded
+}
+
+fn expand_placeholder(token: &str, inputs: &[(String, String)], config: &SnippetConfig) -> Option<String> {
+    match token {
+        "date" => Some(format_now(&config.date_format)),
+        "time" => Some(format_now(&config.time_format)),
+        "clipboard" => Some(read_clipboard()),
+        "uuid" => Some(uuid::Uuid::new_v4().to_string()),
+        _ => {
+            let name = token.strip_prefix("input:")?.trim();
+            inputs.iter()
+                .find(|(field, _)| field == name)
+                .map(|(_, value)| value.clone())
+        }
+    }
+}
+
+fn format_now(format: &str) -> String {
+    use std::fmt::Write as _;
+    
+    let mut formatted = String::new();
+    if write!(formatted, "{}", chrono::Local::now().format(format)).is_err() {
+        formatted = chrono::Local::now().to_rfc3339();
+    }
+    formatted
+}
+
+fn create_default_snippets(snippets_dir: &PathBuf) {
+    let _ = fs::create_dir_all(snippets_dir);
+    let _ = fs::write(
+        snippets_dir.join("sign-off.txt"),
+        "Thanks,\n{input:Name}\n",
+    );
+    let _ = fs::write(
+        snippets_dir.join("timestamp.txt"),
+        "{date} {time}\n",
+    );
+}
+
+fn load_quicklinks() -> Vec<Quicklink> {
+    let config_path = get_config_dir().join("quicklinks.conf");
+    if !config_path.exists() {
+        create_default_quicklinks(&config_path);
+    }
+    
+    let mut quicklinks = Vec::new();
+    
+    if let Ok(content) = fs::read_to_string(&config_path) {
+        for line in content.lines() {
+            let line = line.trim();
+            let parts: Vec<&str> = line.splitn(2, '=').collect();
+            if line.starts_with('#') || parts.len() != 2 {
+                continue;
+            }
+            
+            let keyword = parts[0].trim();
+            let (kind, target) = parts[1].trim().split_once(char::is_whitespace).unwrap_or((parts[1].trim(), ""));
+            let kind = match kind {
+                "url" => QuicklinkKind::Url,
+                "cmd" => QuicklinkKind::Command,
+                "folder" => QuicklinkKind::Folder,
+                _ => continue,
+            };
+            
+            if !keyword.is_empty() && !target.trim().is_empty() {
+                quicklinks.push(Quicklink {
+                    keyword: keyword.to_string(),
+                    kind,
+                    target: target.trim().to_string(),
+                });
+            }
+        }
+    }
+    
+    quicklinks
+}
+
+fn match_quicklinks(quicklinks: &[Quicklink], query: &str, matcher: &dyn FuzzyMatcher) -> Vec<(i64, ResultType)> {
+    let query = query.trim();
+    let (keyword, argument) = query.split_once(char::is_whitespace).unwrap_or((query, ""));
+    let argument = argument.trim();
+    
+    quicklinks.iter()
+        .filter_map(|link| {
+            if link.keyword.eq_ignore_ascii_case(keyword) {
+                return Some((1000, ResultType::Quicklink(link.clone(), argument.to_string())));
+            }
+            
+            if argument.is_empty() {
+                if let Some(score) = matcher.fuzzy_match(&link.keyword, keyword) {
+                    return Some((score + 100, ResultType::Quicklink(link.clone(), String::new())));
+                }
+            }
+            
+            None
+        })
+        .collect()
+}
+
+fn match_games(games: &[SteamGame], query: &str, matcher: &dyn FuzzyMatcher, transliterate: bool) -> Vec<(i64, ResultType)> {
+    if query.is_empty() {
+        return Vec::new();
+    }
+    
+    games.iter()
+        .filter_map(|game| {
+            let name = fold_text(&game.name, transliterate);
+            let (score, indices) = matcher.fuzzy_indices(&name, query)?;
+            Some((score + match_bonus(&name, query, &indices), ResultType::Game(game.clone())))
+        })
+        .collect()
+}
+
+const WORD_BOUNDARY_BONUS: i64 = 8;
+const PREFIX_BONUS: i64 = 40;
+const ACRONYM_CHAR_SCORE: i64 = 40;
+
+fn score_app(
+    search: &AppSearchText,
+    query: &AppQuery,
+    matcher: &dyn FuzzyMatcher,
+    config: &SearchConfig,
+) -> Option<(i64, Vec<usize>)> {
+    let app_query = query;
+    let query = query.text.as_str();
+    let weighted = |score: i64, weight: i64| score * weight / 100;
+    let mut best: Option<(i64, Vec<usize>)> = None;
+    let mut consider = |score: i64, indices: Vec<usize>| {
+        if best.as_ref().is_none_or(|(best_score, _)| score > *best_score) {
+            best = Some((score, indices));
+        }
+    };
+    
+    if config.name_weight > 0 && app_query.may_match(search.name_mask) {
+        if let Some((score, indices)) = matcher.fuzzy_indices(&search.name.text, query) {
+            let bonus = match_bonus(&search.name.text, query, &indices);
+            consider(100 + weighted(score + bonus, config.name_weight), search.name.original_indices(&indices));
+        }
+    }
+    
+    if config.acronym_weight > 0 {
+        if let Some((from_start, indices)) = acronym_indices(&search.initials, &app_query.initials) {
+            let score = indices.len() as i64 * ACRONYM_CHAR_SCORE + if from_start { PREFIX_BONUS } else { 0 };
+            consider(100 + weighted(score, config.acronym_weight), indices);
+        }
+    }
+    
+    if config.generic_name_weight > 0 && app_query.may_match(search.generic_name.char_mask) {
+        if let Some((score, indices)) = matcher.fuzzy_indices(&search.generic_name.text, query) {
+            let bonus = match_bonus(&search.generic_name.text, query, &indices);
+            consider(weighted(score + bonus, config.generic_name_weight), Vec::new());
+        }
+    }
+    
+    let word_fields = [
+        (search.keywords.as_slice(), config.keywords_weight),
+        (search.categories.as_slice(), config.categories_weight),
+        (std::slice::from_ref(&search.comment), config.comment_weight),
+    ];
+    for (values, weight) in word_fields {
+        if weight <= 0 {
+            continue;
+        }
+        if let Some(score) = values.iter()
+            .filter(|value| app_query.may_match(value.char_mask))
+            .filter_map(|value| word_prefix_score(&value.text, query, matcher))
+            .max()
+        {
+            consider(weighted(score, weight), Vec::new());
+        }
+    }
+    
+    if config.exec_weight > 0 && app_query.may_match(search.exec.char_mask) {
+        if let Some(score) = matcher.fuzzy_match(&search.exec.text, query) {
+            consider(weighted(score, config.exec_weight), Vec::new());
+        }
+    }
+    
+    best.filter(|(score, _)| *score > 0)
+}
+
+fn match_bonus(text: &str, query: &str, indices: &[usize]) -> i64 {
+    let chars: Vec<char> = text.chars().collect();
+    let boundaries = indices.iter().filter(|&&i| is_word_start(&chars, i)).count() as i64;
+    let prefix = if text.starts_with(query.trim()) { PREFIX_BONUS } else { 0 };
+    boundaries * WORD_BOUNDARY_BONUS + prefix
+}
+
+fn word_prefix_score(text: &str, query: &str, matcher: &dyn FuzzyMatcher) -> Option<i64> {
+    let first = query.chars().next()?;
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|word| word.starts_with(first))
+        .filter_map(|word| matcher.fuzzy_indices(word, query))
+        .filter(|(_, indices)| indices.first() == Some(&0))
+        .map(|(score, _)| score + PREFIX_BONUS)
+        .max()
+}
+
+fn is_word_start(chars: &[char], i: usize) -> bool {
+    let Some(&c) = chars.get(i) else {
+        return false;
+    };
+    if !c.is_alphanumeric() {
+        return false;
+    }
+    match i.checked_sub(1).map(|prev| chars[prev]) {
+        None => true,
+        Some(prev) => !prev.is_alphanumeric() || (prev.is_lowercase() && c.is_uppercase()),
+    }
+}
+
+fn acronym_indices(initials: &[(usize, Option<char>)], query: &[char]) -> Option<(bool, Vec<usize>)> {
+    if query.len() < 2 || initials.len() < query.len() {
+        return None;
+    }
+    
+    (0..=initials.len() - query.len())
+        .find(|&start| {
+            initials[start..start + query.len()].iter()
+                .zip(query)
+                .all(|((_, initial), q)| *initial == Some(*q))
+        })
+        .map(|start| (start == 0, initials[start..start + query.len()].iter().map(|(i, _)| *i).collect()))
+}
+
+const APP_SCORE_SCALE: f64 = 300.0;
+const TYPO_MIN_SIMILARITY: f64 = 0.6;
+const MAX_TYPO_SUGGESTIONS: usize = 3;
+
+fn typo_similarity(candidate: &str, query: &str) -> f64 {
+    let prefix: String = candidate.chars().take(query.chars().count()).collect();
+    let prefix_similarity = strsim::normalized_damerau_levenshtein(&prefix, query) * 0.9;
+    
+    std::iter::once(candidate)
+        .chain(candidate.split_whitespace())
+        .map(|text| strsim::normalized_damerau_levenshtein(text, query))
+        .fold(prefix_similarity, f64::max)
+}
+
+fn match_apps(
+    apps: &[AppEntry],
+    index: &[AppSearchText],
+    query: &AppQuery,
+    matcher: &dyn FuzzyMatcher,
+    config: &SearchConfig,
+    learned: &std::collections::HashSet<&str>,
+    candidates: Option<&[usize]>,
+) -> CachedSearch {
+    let max_results = config.provider("apps").max_results;
+    let everything: Vec<usize>;
+    let candidates = match candidates {
+        Some(candidates) => candidates,
+        None => {
+            everything = (0..index.len()).collect();
+            &everything
+        }
+    };
+    
+    let possible: Vec<usize> = candidates.par_iter()
+        .copied()
+        .filter(|&i| index[i].may_match(query))
+        .collect();
+    
+    let matches: Vec<(i64, usize, Option<usize>, Vec<usize>)> = possible.par_iter()
+        .map(|&i| (i, &index[i]))
+        .filter_map(|(i, search)| {
+            if let Some((score, indices)) = score_app(search, query, matcher, config) {
+                return Some((score, i, None, indices));
+            }
+            
+            search.actions.iter()
+                .enumerate()
+                .filter(|(_, field)| query.may_match(field.char_mask))
+                .filter_map(|(action, field)| matcher.fuzzy_match(&field.text, &query.text).map(|score| (score, action)))
+                .max_by_key(|(score, _)| *score)
+                .map(|(score, action)| (score, i, Some(action), Vec::new()))
+        })
+        .collect();
+    
+    let mut selected: Vec<usize> = Vec::new();
+    let mut top: BinaryHeap<Reverse<(i64, Reverse<usize>)>> = BinaryHeap::with_capacity(max_results + 1);
+    for (position, (score, i, _, _)) in matches.iter().enumerate() {
+        if learned.contains(apps[*i].desktop_id.as_str()) {
+            selected.push(position);
+            continue;
+        }
+        top.push(Reverse((*score, Reverse(position))));
+        if top.len() > max_results {
+            top.pop();
+        }
+    }
+    selected.extend(top.into_iter().map(|Reverse((_, Reverse(position)))| position));
+    
+    let results = selected.into_iter()
+        .map(|position| {
+            let (score, i, action, indices) = &matches[position];
+            let app = &apps[*i];
+            match action {
+                Some(action) => (*score, ResultType::AppAction(app.clone(), app.actions[*action].clone())),
+                None => {
+                    let mut app_with_match = app.clone();
+                    app_with_match.match_indices = indices.clone();
+                    (*score, ResultType::App(app_with_match))
+                }
+            }
+        })
+        .collect();
+    
+    CachedSearch {
+        candidates: Some(possible),
+        results,
+    }
+}
+
+fn typo_suggestions(
+    apps: &[AppEntry],
+    index: &[AppSearchText],
+    quicklinks: &[Quicklink],
+    query: &str,
+) -> Vec<ResultType> {
+    let query = query.trim();
+    if query.chars().count() < 3 {
+        return Vec::new();
+    }
+    
+    let mut suggestions: Vec<(f64, ResultType)> = apps.par_iter()
+        .zip(index.par_iter())
+        .filter_map(|(app, search)| {
+            let similarity = typo_similarity(&search.name.text, query);
+            (similarity >= TYPO_MIN_SIMILARITY).then(|| (similarity, ResultType::App(app.clone())))
+        })
+        .collect();
+    
+    let keyword = query.split_whitespace().next().unwrap_or(query);
+    suggestions.extend(quicklinks.iter().filter_map(|link| {
+        let similarity = strsim::normalized_damerau_levenshtein(&link.keyword.to_lowercase(), keyword);
+        (similarity >= TYPO_MIN_SIMILARITY).then(|| (similarity, ResultType::Quicklink(link.clone(), String::new())))
+    }));
+    
+    suggestions.sort_by(|a, b| b.0.total_cmp(&a.0));
+    suggestions.into_iter()
+        .take(MAX_TYPO_SUGGESTIONS)
+        .map(|(_, suggestion)| suggestion)
+        .collect()
+}
+
+fn expand_home(path: &str) -> PathBuf {
+    match (path.strip_prefix("~"), dirs::home_dir()) {
+        (Some(rest), Some(home)) => home.join(rest.trim_start_matches(['/', '\\'])),
+        _ => PathBuf::from(path),
+    }
+}
+
+fn create_default_quicklinks(config_path: &PathBuf) {
+    let default_quicklinks = r#"# Flint Quicklinks
+# Format: keyword=type target
+# Types: url (opened in the browser), cmd (run as a command), folder (opened in the file manager)
+# {} is replaced with whatever you type after the keyword, e.g. "jira PROJ-123"
+
+# jira=url https://jira.example.com/browse/{}
+# deploy-logs=cmd kubectl logs -f deployment/api
+# specs=folder ~/Documents/specs
+"#;
+
+    if let Some(parent) = config_path.parent() {
+        let _ = fs::create_dir_all(parent);
+    }
+    let _ = fs::write(config_path, default_quicklinks);
+}
+
+fn search_emojis(query: &str, transliterate: bool) -> Vec<(String, String)> {
+    let query_lower = fold_text(query, transliterate);
+    
+    let common_aliases: Vec<(&str, &str)> = vec![
+        ("smile", "😊"), ("happy", "😊"), ("laugh", "😂"), ("heart", "❤️"), ("love", "❤️"),
+        ("kiss", "😘"), ("cool", "😎"), ("thinking", "🤔"), ("thumbsup", "👍"), ("like", "👍"),
+        ("ok", "👌"), ("clap", "👏"), ("pray", "🙏"), ("wave", "👋"), ("muscle", "💪"),
+        ("eyes", "👀"), ("cat", "🐱"), ("dog", "🐶"), ("car", "🚗"), ("plane", "✈️"),
+        ("rocket", "🚀"), ("computer", "💻"), ("phone", "📱"), ("camera", "📷"), ("music", "🎵"),
+        ("game", "🎮"), ("food", "🍕"), ("coffee", "☕"), ("beer", "🍺"), ("fire", "🔥"),
+        ("star", "⭐"), ("money", "💰"), ("clock", "⏰"), ("email", "📧"), ("book", "📖"),
+    ];
+    
+    let alias_results: Vec<(String, String)> = common_aliases
+        .iter()
+        .filter(|(alias, _)| alias.contains(&query_lower))
+        .map(|(alias, emoji)| (alias.to_string(), emoji.to_string()))
+        .take(3)
+        .collect();
+    
+    let crate_results: Vec<(String, String)> = emojis::iter()
+        .filter_map(|emoji| {
+            if fold_text(emoji.name(), transliterate).contains(&query_lower) {
+                Some((emoji.name().to_string(), emoji.as_str().to_string()))
+            } else {
+                None
+            }
+        })
+        .take(2)
+        .collect();
+    
+    let mut combined = alias_results;
+    for result in crate_results {
+        if !combined.iter().any(|(_, emoji)| emoji == &result.1) {
+            combined.push(result);
+        }
+    }
+    
+    combined.truncate(5);
+    combined
+}
+
+fn looks_like_url(text: &str) -> bool {
+    let text = text.trim();
+    
+    if text.contains("://") {
+        return text.starts_with("http://") || text.starts_with("https://");
+    }
+    
+    if text.contains('.') && !text.contains(' ') {
+        let domain_part = if text.contains('/') {
+            text.split('/').next().unwrap_or("")
+        } else {
+            text
+        };
+        
+        let parts: Vec<&str> = domain_part.split('.').collect();
+        if parts.len() >= 2 {
+            let last_part = parts.last().unwrap();
+            
+            let common_tlds = [
+                "com", "org", "net", "io", "co", "me", "dev", "app", "tech", "xyz",
+                "us", "uk", "ca", "au", "de", "fr", "jp", "in", "br", "ru",
+            ];
+            
+            return common_tlds.contains(last_part) || 
+                   last_part.len() == 2;
+        }
+    }
+    
+    false
+}
+
+fn acquire_lock() -> Result<File, String> {
+    let lock_path = get_lock_path();
+    
+    if lock_path.exists() {
+        return Err("Flint is already running!".to_string());
+    }
+    
+    let mut lock_file = OpenOptions::new()
+        .write(true)
+        .create(true)
+        .truncate(true)
+        .open(&lock_path)
+        .map_err(|e| format!("Failed to create lock file: {}", e))?;
+    
+    let pid = std::process::id();
+    lock_file.write_all(pid.to_string().as_bytes())
+        .map_err(|e| format!("Failed to write PID: {}", e))?;
+    
+    Ok(lock_file)
+}
+
+fn get_lock_path() -> PathBuf {
+    std::env::temp_dir().join("flint.lock")
+}
+
+#[cfg(target_os = "windows")]
+fn launch_app(app: &AppEntry, exec: &str, config: &CommandConfig) -> Result<(), String> {
+    use std::os::windows::process::CommandExt;
+    
+    let argv = exec_argv(app, exec);
+    if argv.is_empty() {
+        return Err(format!("{} has no command to run", app.name));
+    }
+    let (argv, flags) = if app.terminal {
+        (config.terminal_command(argv), CREATE_NEW_CONSOLE | CREATE_NEW_PROCESS_GROUP)
+    } else {
+        (argv, DETACHED_PROCESS | CREATE_NEW_PROCESS_GROUP)
+    };
+    
+    if !app.terminal && !is_windows_program(std::path::Path::new(&argv[0])) {
+        info!(app = %app.name, argv = ?argv, "opening with the shell");
+        return shell_open(&argv[0], &argv[1..], app.working_dir.as_deref())
+            .map_err(|e| format!("Failed to open {}: {}", app.name, e));
+    }
+    
+    info!(app = %app.name, argv = ?argv, "launching application");
+    let mut command = Command::new(&argv[0]);
+    command.args(&argv[1..])
+        .stdin(std::process::Stdio::null())
+        .creation_flags(flags);
+    apply_launch_environment(&mut command, app);
+    
+    command.spawn()
+        .map(|_| ())
+        .map_err(|e| {
+            warn!(app = %app.name, program = %argv[0], "failed to spawn application: {}", e);
+            format!("Failed to launch {} ({}): {}", app.name, argv[0], e)
+        })
+}
+
+#[cfg(target_os = "windows")]
+fn is_windows_program(path: &std::path::Path) -> bool {
+    const PROGRAM_EXTENSIONS: [&str; 4] = ["exe", "com", "bat", "cmd"];
+    
+    !path.is_dir() && path.extension()
+        .and_then(|e| e.to_str())
+        .is_none_or(|e| PROGRAM_EXTENSIONS.iter().any(|program| e.eq_ignore_ascii_case(program)))
+}
+
+#[cfg(target_os = "windows")]
+#[link(name = "shell32")]
+extern "system" {
+    fn ShellExecuteW(
+        hwnd: *mut std::ffi::c_void,
+        operation: *const u16,
+        file: *const u16,
+        parameters: *const u16,
+        directory: *const u16,
+        show_cmd: i32,
+    ) -> isize;
+}
+
+#[cfg(target_os = "windows")]
+fn shell_open(file: &str, arguments: &[String], working_dir: Option<&std::path::Path>) -> Result<(), String> {
+    use std::os::windows::ffi::OsStrExt;
+    const SW_SHOWNORMAL: i32 = 1;
+    
+    let wide = |text: &std::ffi::OsStr| text.encode_wide().chain(Some(0)).collect::<Vec<u16>>();
+    let operation = wide("open".as_ref());
+    let file = wide(file.as_ref());
+    let parameters = wide(join_windows_args(arguments).as_ref());
+    let directory = working_dir.filter(|dir| dir.is_dir()).map(|dir| wide(dir.as_os_str()));
+    
+    // SAFETY: every pointer refers to a NUL-terminated UTF-16 buffer that outlives the call,
+    // and ShellExecuteW accepts a null owner window and a null directory.
+    let result = unsafe {
+        ShellExecuteW(
+            std::ptr::null_mut(),
+            operation.as_ptr(),
+            file.as_ptr(),
+            parameters.as_ptr(),
+            directory.as_ref().map_or(std::ptr::null(), |dir| dir.as_ptr()),
+            SW_SHOWNORMAL,
+        )
+    };
+    if result <= 32 {
+        return Err(format!("ShellExecute failed with code {}", result));
+    }
+    Ok(())
+}
+
+#[cfg(not(target_os = "windows"))]
+fn launch_app(app: &AppEntry, exec: &str, config: &CommandConfig) -> Result<(), String> {
+    use std::os::unix::process::CommandExt;
+    
+    let argv = exec_argv(app, exec);
+    if argv.is_empty() {
+        return Err(format!("{} has no command to run", app.name));
+    }
+    let argv = if app.terminal { config.terminal_command(argv) } else { argv };
+    
+    let mut command = Command::new(&argv[0]);
+    command.args(&argv[1..])
+        .stdin(std::process::Stdio::null());
+    apply_launch_environment(&mut command, app);
+    // SAFETY: the closure runs in the forked child before exec. It only calls setsid(2),
+    // which is async-signal-safe, and builds its error from errno without allocating.
+    unsafe {
+        command.pre_exec(|| {
+            if libc::setsid() == -1 {
+                return Err(std::io::Error::last_os_error());
+            }
+            Ok(())
+        });
+    }
+    
+    info!(app = %app.name, argv = ?argv, "launching application");
+    let mut child = command.spawn()
+        .map_err(|e| format!("Failed to launch {} ({}): {}", app.name, argv[0], e))?;
+    thread::spawn(move || {
+        let _ = child.wait();
+    });
+    Ok(())
+}
+
+#[cfg(target_os = "windows")]
+fn scan_apps() -> Vec<AppEntry> {
+    scan_windows_apps()
+}
+
+#[cfg(not(target_os = "windows"))]
+fn scan_apps() -> Vec<AppEntry> {
+    scan_linux_apps()
+}
+
+#[cfg(target_os = "windows")]
+fn scan_windows_apps() -> Vec<AppEntry> {
+    let filters = load_app_filters();
+    let mut versions = VersionIndex::load();
+    let mut apps = scan_start_menu(&filters, &mut versions);
+    apps.extend(scan_desktop_shortcuts());
+    let shortcut_targets: std::collections::HashSet<String> = apps.iter()
+        .filter_map(|app| tokenize_exec(&app.exec_command).into_iter().next())
+        .map(|target| target.to_lowercase())
+        .collect();
+    let shortcut_programs: std::collections::HashSet<String> = shortcut_targets.iter()
+        .filter_map(|target| target.rsplit(['\\', '/']).next().map(str::to_string))
+        .collect();
+
+    let common_apps = [
+        ("Notepad", "notepad.exe"),
+        ("Calculator", "calc.exe"), 
+        ("Paint", "mspaint.exe"),
+        ("Command Prompt", "cmd.exe"),
+        ("PowerShell", "powershell.exe"),
+        ("File Explorer", "explorer.exe"),
+        ("Task Manager", "taskmgr.exe"),
+        ("Control Panel", "control.exe"),
+        ("System Configuration", "msconfig.exe"),
+        ("Registry Editor", "regedit.exe"),
+        ("Windows Media Player", "wmplayer.exe"),
+        ("WordPad", "write.exe"),
+        ("Snipping Tool", "snippingtool.exe"),
+        ("Sticky Notes", "stikynot.exe"),
+    ];
+
+    for (name, exec) in common_apps {
+        if shortcut_programs.contains(exec) || apps.iter().any(|app| app.name == name) {
+            continue;
+        }
+        apps.push(AppEntry {
+            name: name.to_string(),
+            desktop_id: name.to_string(),
+            exec_command: exec.to_string(),
+            match_indices: Vec::new(),
+            generic_name: String::new(),
+            keywords: Vec::new(),
+            categories: Vec::new(),
+            comment: String::new(),
+            source: AppSource::Native,
+            actions: Vec::new(),
+            terminal: false,
+            icon: String::new(),
+            working_dir: None,
+            desktop_file: None,
+        });
+    }
+
+    let program_dirs = [
+        std::env::var("PROGRAMFILES").unwrap_or_else(|_| "C:\\Program Files".to_string()),
+        std::env::var("PROGRAMFILES(X86)").unwrap_or_else(|_| "C:\\Program Files (x86)".to_string()),
+        std::env::var("LOCALAPPDATA").unwrap_or_else(|_| "C:\\Users\\Default\\AppData\\Local".to_string()),
+    ];
+
+    for program_dir in &program_dirs {
+        let program_path = PathBuf::from(program_dir);
+        if program_path.exists() {
+            if let Ok(entries) = fs::read_dir(&program_path) {
+                for entry in entries.flatten() {
+                    let path = entry.path();
+                    if path.is_dir() {
+                        if let Some(folder_name) = path.file_name().and_then(|s| s.to_str()) {
+                            if let Ok(sub_entries) = fs::read_dir(&path) {
+                                for sub_entry in sub_entries.flatten() {
+                                    let sub_path = sub_entry.path();
+                                    if sub_path.extension().and_then(|e| e.to_str()) == Some("exe") {
+                                        if shortcut_targets.contains(&sub_path.display().to_string().to_lowercase()) {
+                                            continue;
+                                        }
+                                        if let Some(exe_name) = sub_path.file_stem().and_then(|s| s.to_str()) {
+                                            if is_filtered_app(&filters, &[exe_name]) {
+                                                continue;
+                                            }
+                                            let version = versions.get(&sub_path);
+                                            let name = version.display_name()
+                                                .unwrap_or_else(|| format!("{} - {}", folder_name, exe_name));
+                                            if is_filtered_app(&filters, &[&name]) {
+                                                continue;
+                                            }
+                                            apps.push(AppEntry {
+                                                comment: version.subtitle(&name),
+                                                name,
+                                                desktop_id: folder_name.to_string(),
+                                                exec_command: quote_exec_arg(&sub_path.display().to_string()),
+                                                match_indices: Vec::new(),
+                                                generic_name: String::new(),
+                                                keywords: Vec::new(),
+                                                categories: Vec::new(),
+                                                source: AppSource::Native,
+                                                actions: Vec::new(),
+                                                terminal: false,
+                                                icon: String::new(),
+                                                working_dir: None,
+                                                desktop_file: None,
+                                            });
+                                        }
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+    versions.save();
+
+    dedup_launch_commands(&mut apps);
+    apps.sort_by(|a, b| a.name.cmp(&b.name));
+    apps
+}
+
+#[cfg(any(target_os = "windows", test))]
+fn dedup_launch_commands(apps: &mut Vec<AppEntry>) {
+    let mut seen = std::collections::HashSet::new();
+    apps.retain(|app| {
+        let argv: Vec<String> = tokenize_exec(&app.exec_command).iter().map(|arg| arg.to_lowercase()).collect();
+        seen.insert(argv)
+    });
+}
+
+#[cfg(target_os = "windows")]
+fn start_menu_dirs() -> Vec<PathBuf> {
+    ["APPDATA", "PROGRAMDATA"].into_iter()
+        .filter_map(|var| std::env::var(var).ok())
+        .map(|base| PathBuf::from(base).join("Microsoft\\Windows\\Start Menu\\Programs"))
+        .filter(|dir| dir.is_dir())
+        .collect()
+}
+
+#[cfg(target_os = "windows")]
+fn collect_shortcuts(dir: &std::path::Path, files: &mut Vec<PathBuf>) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+    
+    let mut paths: Vec<PathBuf> = entries.flatten().map(|entry| entry.path()).collect();
+    paths.sort();
+    
+    for path in paths {
+        if path.is_dir() {
+            collect_shortcuts(&path, files);
+        } else if path.extension().and_then(|e| e.to_str()).is_some_and(|e| e.eq_ignore_ascii_case("lnk") || e.eq_ignore_ascii_case("url")) {
+            files.push(path);
+        }
+    }
+}
+
+#[cfg(target_os = "windows")]
+fn scan_start_menu(filters: &[String], versions: &mut VersionIndex) -> Vec<AppEntry> {
+    let cached = load_shortcut_index();
+    let mut index = std::collections::HashMap::new();
+    let mut apps = Vec::new();
+    let mut parsed = 0;
+    
+    for dir in start_menu_dirs() {
+        let mut shortcuts = Vec::new();
+        collect_shortcuts(&dir, &mut shortcuts);
+        
+        for path in shortcuts {
+            if path.extension().and_then(|e| e.to_str()).is_some_and(|e| e.eq_ignore_ascii_case("url")) {
+                apps.extend(internet_shortcut_app(&path));
+                continue;
+            }
+            
+            let modified = modified_secs(&path);
+            let link = match cached.get(&path) {
+                Some((cached_modified, link)) if *cached_modified == modified => link.clone(),
+                _ => match fs::read(&path).map_err(|e| e.to_string()).and_then(|data| parse_shell_link(&data)) {
+                    Ok(link) => {
+                        parsed += 1;
+                        link
+                    }
+                    Err(e) => {
+                        debug!(path = %path.display(), "skipping shortcut: {}", e);
+                        continue;
+                    }
+                },
+            };
+            
+            apps.extend(shortcut_app(&path, &link, filters, versions));
+            index.insert(path, (modified, link));
+        }
+    }
+    
+    if parsed > 0 || index.len() != cached.len() {
+        save_shortcut_index(&index);
+    }
+    debug!(shortcuts = index.len(), parsed, "scanned Start Menu shortcuts");
+    apps
+}
+
+#[cfg(target_os = "windows")]
+fn shortcut_app(path: &std::path::Path, link: &ShellLink, filters: &[String], versions: &mut VersionIndex) -> Option<AppEntry> {
+    let name = path.file_stem()?.to_str()?.to_string();
+    let target = if !link.target.is_empty() {
+        expand_environment_strings(&link.target)
+    } else if !link.relative_path.is_empty() {
+        path.parent()?.join(&link.relative_path).display().to_string()
+    } else {
+        return None;
+    };
+    
+    let target_path = std::path::Path::new(&target);
+    let target_name = target_path.file_stem().and_then(|s| s.to_str()).unwrap_or_default();
+    if is_filtered_app(filters, &[&name, target_name]) {
+        return None;
+    }
+    
+    let comment = if !link.description.is_empty() && !link.description.starts_with('@') {
+        link.description.clone()
+    } else if target_path.extension().and_then(|e| e.to_str()).is_some_and(|e| e.eq_ignore_ascii_case("exe")) {
+        versions.get(target_path).subtitle(&name)
+    } else {
+        String::new()
+    };
+    
+    let exec_command = shortcut_exec(&target, &expand_environment_strings(&link.arguments));
+    let icon = if link.icon_location.is_empty() {
+        target
+    } else {
+        expand_environment_strings(&link.icon_location)
+    };
+    
+    Some(AppEntry {
+        name,
+        desktop_id: path.display().to_string(),
+        exec_command,
+        match_indices: Vec::new(),
+        generic_name: String::new(),
+        keywords: Vec::new(),
+        categories: Vec::new(),
+        comment,
+        source: AppSource::Native,
+        actions: Vec::new(),
+        terminal: false,
+        icon,
+        working_dir: (!link.working_dir.is_empty()).then(|| PathBuf::from(expand_environment_strings(&link.working_dir))),
+        desktop_file: Some(path.to_path_buf()),
+    })
+}
+
+#[cfg(target_os = "windows")]
+fn load_shortcut_index() -> std::collections::HashMap<PathBuf, (u64, ShellLink)> {
+    fs::read_to_string(get_config_dir().join("shortcut_index"))
+        .map(|content| {
+            content.lines()
+                .filter_map(|line| {
+                    let fields: Vec<&str> = line.split('\t').collect();
+                    let [path, modified, target, arguments, working_dir, icon_location, icon_index, description, relative_path] = fields[..] else {
+                        return None;
+                    };
+                    let link = ShellLink {
+                        target: target.to_string(),
+                        arguments: arguments.to_string(),
+                        working_dir: working_dir.to_string(),
+                        icon_location: icon_location.to_string(),
+                        icon_index: icon_index.parse().ok()?,
+                        description: description.to_string(),
+                        relative_path: relative_path.to_string(),
+                    };
+                    Some((PathBuf::from(path), (modified.parse().ok()?, link)))
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+#[cfg(target_os = "windows")]
+fn save_shortcut_index(index: &std::collections::HashMap<PathBuf, (u64, ShellLink)>) {
+    let lines: Vec<String> = index.iter()
+        .map(|(path, (modified, link))| {
+            [
+                path.display().to_string(),
+                modified.to_string(),
+                link.target.clone(),
+                link.arguments.clone(),
+                link.working_dir.clone(),
+                link.icon_location.clone(),
+                link.icon_index.to_string(),
+                link.description.clone(),
+                link.relative_path.clone(),
+            ]
+        })
+        .filter(|fields| !fields.iter().any(|field| field.contains(['\t', '\n', '\r'])))
+        .map(|fields| fields.join("\t"))
+        .collect();
+    
+    let config_dir = get_config_dir();
+    let _ = fs::create_dir_all(&config_dir);
+    let _ = fs::write(config_dir.join("shortcut_index"), lines.join("\n"));
+}
+
+#[cfg(any(target_os = "windows", test))]
+fn shortcut_exec(target: &str, arguments: &str) -> String {
+    std::iter::once(target.to_string())
+        .chain(split_windows_args(arguments))
+        .map(|arg| quote_exec_arg(&arg))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+#[cfg(any(target_os = "windows", test))]
+fn split_windows_args(arguments: &str) -> Vec<String> {
+    let mut args = Vec::new();
+    let mut current = String::new();
+    let mut in_arg = false;
+    let mut quoted = false;
+    let mut chars = arguments.chars().peekable();
+    
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => {
+                let mut backslashes = 1;
+                while chars.next_if_eq(&'\\').is_some() {
+                    backslashes += 1;
+                }
+                if chars.peek() == Some(&'"') {
+                    current.extend(std::iter::repeat_n('\\', backslashes / 2));
+                    if backslashes % 2 == 1 {
+                        current.push('"');
+                        chars.next();
+                    }
+                } else {
+                    current.extend(std::iter::repeat_n('\\', backslashes));
+                }
+                in_arg = true;
+            }
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                current.push('"');
+                chars.next();
+            }
+            '"' => {
+                quoted = !quoted;
+                in_arg = true;
+            }
+            ' ' | '\t' if !quoted => {
+                if in_arg {
+                    args.push(std::mem::take(&mut current));
+                    in_arg = false;
+                }
+            }
+            c => {
+                current.push(c);
+                in_arg = true;
+            }
+        }
+    }
+    if in_arg {
+        args.push(current);
+    }
+    
+    args
+}
+
+#[cfg(any(target_os = "windows", test))]
+fn join_windows_args(args: &[String]) -> String {
+    let mut joined = String::new();
+    
+    for arg in args {
+        if !joined.is_empty() {
+            joined.push(' ');
+        }
+        if !arg.is_empty() && !arg.contains([' ', '\t', '"']) {
+            joined.push_str(arg);
+            continue;
+        }
+        
+        joined.push('"');
+        let mut backslashes = 0;
+        for c in arg.chars() {
+            match c {
+                '\\' => backslashes += 1,
+                '"' => {
+                    joined.extend(std::iter::repeat_n('\\', backslashes * 2 + 1));
+                    joined.push('"');
+                    backslashes = 0;
+                }
+                c => {
+                    joined.extend(std::iter::repeat_n('\\', backslashes));
+                    joined.push(c);
+                    backslashes = 0;
+                }
+            }
+        }
+        joined.extend(std::iter::repeat_n('\\', backslashes * 2));
+        joined.push('"');
+    }
+    
+    joined
+}
+
+#[cfg(any(target_os = "windows", test))]
+fn quote_exec_arg(arg: &str) -> String {
+    let mut quoted = String::from("\"");
+    for c in arg.chars() {
+        match c {
+            '"' | '`' | '$' | '\\' => {
+                quoted.push('\\');
+                quoted.push(c);
+            }
+            '%' => quoted.push_str("%%"),
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+#[cfg(target_os = "windows")]
+fn expand_environment_strings(text: &str) -> String {
+    let mut expanded = String::new();
+    let mut rest = text;
+    
+    while let Some(start) = rest.find('%') {
+        expanded.push_str(&rest[..start]);
+        let after = &rest[start + 1..];
+        match after.find('%').and_then(|end| std::env::var(&after[..end]).ok().map(|value| (end, value))) {
+            Some((end, value)) => {
+                expanded.push_str(&value);
+                rest = &after[end + 1..];
+            }
+            None => {
+                expanded.push('%');
+                rest = after;
+            }
+        }
+    }
+    expanded.push_str(rest);
+    expanded
+}
+
+#[cfg(any(target_os = "windows", test))]
+#[derive(Clone, Debug, Default, PartialEq)]
+struct ShellLink {
+    target: String,
+    arguments: String,
+    working_dir: String,
+    icon_location: String,
+    icon_index: i32,
+    description: String,
+    relative_path: String,
+}
+
+#[cfg(any(target_os = "windows", test))]
+fn parse_shell_link(data: &[u8]) -> Result<ShellLink, String> {
+    const SHELL_LINK_HEADER_SIZE: usize = 0x4C;
+    const SHELL_LINK_CLSID: [u8; 16] = [
+        0x01, 0x14, 0x02, 0x00, 0x00, 0x00, 0x00, 0x00, 0xC0, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x46,
+    ];
+    const LINK_HAS_ID_LIST: u32 = 0x01;
+    const LINK_HAS_LINK_INFO: u32 = 0x02;
+    const LINK_HAS_NAME: u32 = 0x04;
+    const LINK_HAS_RELATIVE_PATH: u32 = 0x08;
+    const LINK_HAS_WORKING_DIR: u32 = 0x10;
+    const LINK_HAS_ARGUMENTS: u32 = 0x20;
+    const LINK_HAS_ICON_LOCATION: u32 = 0x40;
+    const LINK_IS_UNICODE: u32 = 0x80;
+    const ENVIRONMENT_BLOCK_SIGNATURE: u32 = 0xA000_0001;
+    const ICON_ENVIRONMENT_BLOCK_SIGNATURE: u32 = 0xA000_0007;
+    
+    let truncated = || "Shortcut is truncated".to_string();
+    
+    if read_u32(data, 0) != Some(SHELL_LINK_HEADER_SIZE as u32) || data.get(4..20) != Some(&SHELL_LINK_CLSID[..]) {
+        return Err("Not a shell link".to_string());
+    }
+    let flags = read_u32(data, 0x14).ok_or_else(truncated)?;
+    let mut link = ShellLink {
+        icon_index: read_u32(data, 0x38).ok_or_else(truncated)? as i32,
+        ..ShellLink::default()
+    };
+    let mut offset = SHELL_LINK_HEADER_SIZE;
+    
+    if flags & LINK_HAS_ID_LIST != 0 {
+        offset += 2 + read_u16(data, offset).ok_or_else(truncated)? as usize;
+    }
+    
+    if flags & LINK_HAS_LINK_INFO != 0 {
+        let size = read_u32(data, offset).ok_or_else(truncated)? as usize;
+        let info = data.get(offset..offset + size).ok_or_else(truncated)?;
+        link.target = parse_link_info(info).unwrap_or_default();
+        offset += size;
+    }
+    
+    let unicode = flags & LINK_IS_UNICODE != 0;
+    let string_data = [
+        (LINK_HAS_NAME, &mut link.description),
+        (LINK_HAS_RELATIVE_PATH, &mut link.relative_path),
+        (LINK_HAS_WORKING_DIR, &mut link.working_dir),
+        (LINK_HAS_ARGUMENTS, &mut link.arguments),
+        (LINK_HAS_ICON_LOCATION, &mut link.icon_location),
+    ];
+    for (flag, field) in string_data {
+        if flags & flag == 0 {
+            continue;
+        }
+        let count = read_u16(data, offset).ok_or_else(truncated)? as usize;
+        let size = if unicode { count * 2 } else { count };
+        let bytes = data.get(offset + 2..offset + 2 + size).ok_or_else(truncated)?;
+        *field = if unicode { decode_utf16(bytes) } else { decode_ansi(bytes) };
+        offset += 2 + size;
+    }
+    
+    while let Some(size) = read_u32(data, offset).map(|size| size as usize) {
+        let Some(block) = data.get(offset..offset + size).filter(|_| size >= 8) else {
+            break;
+        };
+        match read_u32(block, 4) {
+            Some(ENVIRONMENT_BLOCK_SIGNATURE) if link.target.is_empty() => {
+                link.target = environment_block_path(block);
+            }
+            Some(ICON_ENVIRONMENT_BLOCK_SIGNATURE) if link.icon_location.is_empty() => {
+                link.icon_location = environment_block_path(block);
+            }
+            _ => {}
+        }
+        offset += size;
+    }
+    
+    Ok(link)
+}
+
+#[cfg(any(target_os = "windows", test))]
+fn parse_link_info(info: &[u8]) -> Option<String> {
+    const LINK_INFO_LOCAL_PATH: u32 = 0x01;
+    const LINK_INFO_NETWORK_PATH: u32 = 0x02;
+    
+    let header_size = read_u32(info, 4)?;
+    let flags = read_u32(info, 8)?;
+    let unicode_offset = |field: usize| {
+        (header_size >= 0x24)
+            .then(|| read_u32(info, field))
+            .flatten()
+            .filter(|&offset| offset != 0)
+            .and_then(|offset| read_utf16_c_string(info, offset as usize))
+    };
+    
+    let suffix = unicode_offset(0x20)
+        .or_else(|| read_c_string(info, read_u32(info, 0x18)? as usize))
+        .unwrap_or_default();
+    
+    if flags & LINK_INFO_LOCAL_PATH != 0 {
+        let base = unicode_offset(0x1C)
+            .or_else(|| read_c_string(info, read_u32(info, 0x10)? as usize))?;
+        return Some(base + &suffix);
+    }
+    
+    if flags & LINK_INFO_NETWORK_PATH != 0 {
+        let network = info.get(read_u32(info, 0x14)? as usize..)?;
+        let share = read_c_string(network, read_u32(network, 8)? as usize)?;
+        return Some(if suffix.is_empty() { share } else { format!("{}\\{}", share.trim_end_matches('\\'), suffix) });
+    }
+    
+    None
+}
+
+#[cfg(any(target_os = "windows", test))]
+fn environment_block_path(block: &[u8]) -> String {
+    block.get(268..788)
+        .map(decode_utf16)
+        .filter(|path| !path.is_empty())
+        .or_else(|| block.get(8..268).map(decode_ansi))
+        .unwrap_or_default()
+}
+
+#[cfg(any(target_os = "windows", test))]
+fn read_u16(data: &[u8], offset: usize) -> Option<u16> {
+    data.get(offset..offset + 2).map(|bytes| u16::from_le_bytes([bytes[0], bytes[1]]))
+}
+
+#[cfg(any(target_os = "windows", test))]
+fn read_u32(data: &[u8], offset: usize) -> Option<u32> {
+    data.get(offset..offset + 4).map(|bytes| u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+}
+
+#[cfg(any(target_os = "windows", test))]
+fn read_c_string(data: &[u8], offset: usize) -> Option<String> {
+    let bytes = data.get(offset..)?;
+    let end = bytes.iter().position(|&b| b == 0).unwrap_or(bytes.len());
+    Some(decode_ansi(&bytes[..end]))
+}
+
+#[cfg(any(target_os = "windows", test))]
+fn read_utf16_c_string(data: &[u8], offset: usize) -> Option<String> {
+    let bytes = data.get(offset..)?;
+    let end = bytes.chunks_exact(2).position(|unit| unit == [0, 0]).map_or(bytes.len(), |units| units * 2);
+    Some(decode_utf16(&bytes[..end]))
+}
+
+#[cfg(any(target_os = "windows", test))]
+fn decode_utf16(bytes: &[u8]) -> String {
+    let units: Vec<u16> = bytes.chunks_exact(2)
+        .map(|unit| u16::from_le_bytes([unit[0], unit[1]]))
+        .take_while(|&unit| unit != 0)
+        .collect();
+    String::from_utf16_lossy(&units)
+}
+
+#[cfg(any(target_os = "windows", test))]
+fn decode_ansi(bytes: &[u8]) -> String {
+    bytes.iter()
+        .take_while(|&&b| b != 0)
+        .map(|&b| b as char)
+        .collect()
+}
+
+#[cfg(target_os = "windows")]
+fn modified_secs(path: &std::path::Path) -> u64 {
+    fs::metadata(path)
+        .and_then(|metadata| metadata.modified())
+        .ok()
+        .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+        .map(|elapsed| elapsed.as_secs())
+        .unwrap_or_default()
+}
+
+#[cfg(any(target_os = "windows", test))]
+#[derive(Clone, Debug, Default, PartialEq)]
+struct VersionInfo {
+    product_name: String,
+    file_description: String,
+    company_name: String,
+}
+
+#[cfg(target_os = "windows")]
+impl VersionInfo {
+    fn display_name(&self) -> Option<String> {
+        [&self.file_description, &self.product_name].into_iter()
+            .find(|name| !name.is_empty())
+            .cloned()
+    }
+    
+    fn subtitle(&self, name: &str) -> String {
+        let product = Some(self.product_name.as_str()).filter(|product| !product.eq_ignore_ascii_case(name));
+        product.into_iter()
+            .chain(Some(self.company_name.as_str()))
+            .filter(|part| !part.is_empty())
+            .collect::<Vec<_>>()
+            .join(" · ")
+    }
+}
+
+#[cfg(target_os = "windows")]
+struct VersionIndex {
+    cached: std::collections::HashMap<PathBuf, (u64, VersionInfo)>,
+    entries: std::collections::HashMap<PathBuf, (u64, VersionInfo)>,
+    parsed: usize,
+}
+
+#[cfg(target_os = "windows")]
+impl VersionIndex {
+    fn load() -> Self {
+        let cached = fs::read_to_string(get_config_dir().join("version_index"))
+            .map(|content| {
+                content.lines()
+                    .filter_map(|line| {
+                        let fields: Vec<&str> = line.split('\t').collect();
+                        let [path, modified, product_name, file_description, company_name] = fields[..] else {
+                            return None;
+                        };
+                        let info = VersionInfo {
+                            product_name: product_name.to_string(),
+                            file_description: file_description.to_string(),
+                            company_name: company_name.to_string(),
+                        };
+                        Some((PathBuf::from(path), (modified.parse().ok()?, info)))
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+        
+        Self { cached, entries: std::collections::HashMap::new(), parsed: 0 }
+    }
+    
+    fn get(&mut self, path: &std::path::Path) -> VersionInfo {
+        if let Some((_, info)) = self.entries.get(path) {
+            return info.clone();
+        }
+        
+        let modified = modified_secs(path);
+        let info = match self.cached.get(path) {
+            Some((cached_modified, info)) if *cached_modified == modified => info.clone(),
+            _ => {
+                self.parsed += 1;
+                read_version_info(path).unwrap_or_else(|e| {
+                    debug!(path = %path.display(), "no version info: {}", e);
+                    VersionInfo::default()
+                })
+            }
+        };
+        self.entries.insert(path.to_path_buf(), (modified, info.clone()));
+        info
+    }
+    
+    fn save(&self) {
+        if self.parsed == 0 && self.entries.len() == self.cached.len() {
+            return;
+        }
+        
+        let lines: Vec<String> = self.entries.iter()
+            .map(|(path, (modified, info))| {
+                [
+                    path.display().to_string(),
+                    modified.to_string(),
+                    info.product_name.clone(),
+                    info.file_description.clone(),
+                    info.company_name.clone(),
+                ]
+            })
+            .filter(|fields| !fields.iter().any(|field| field.contains(['\t', '\n', '\r'])))
+            .map(|fields| fields.join("\t"))
+            .collect();
+        
+        let config_dir = get_config_dir();
+        let _ = fs::create_dir_all(&config_dir);
+        let _ = fs::write(config_dir.join("version_index"), lines.join("\n"));
+        debug!(executables = self.entries.len(), parsed = self.parsed, "saved version index");
+    }
+}
+
+#[cfg(any(target_os = "windows", test))]
+fn read_version_info(path: &std::path::Path) -> Result<VersionInfo, String> {
+    use std::io::{Read, Seek, SeekFrom};
+    const PE_HEADERS_READ_SIZE: u64 = 4096;
+    const MAX_RESOURCE_SECTION_SIZE: usize = 32 * 1024 * 1024;
+    
+    let mut file = File::open(path).map_err(|e| e.to_string())?;
+    let mut headers = Vec::new();
+    (&mut file).take(PE_HEADERS_READ_SIZE).read_to_end(&mut headers).map_err(|e| e.to_string())?;
+    
+    let (file_offset, size, section_rva, resource_rva) = pe_resource_section(&headers)?;
+    if size > MAX_RESOURCE_SECTION_SIZE {
+        return Err("Resource section is too large".to_string());
+    }
+    let mut section = vec![0; size];
+    file.seek(SeekFrom::Start(file_offset))
+        .and_then(|_| file.read_exact(&mut section))
+        .map_err(|e| e.to_string())?;
+    
+    parse_version_resource(&section, section_rva, resource_rva)
+}
+
+#[cfg(any(target_os = "windows", test))]
+fn pe_resource_section(headers: &[u8]) -> Result<(u64, usize, u32, u32), String> {
+    const OPTIONAL_HEADER_PE32: u16 = 0x10B;
+    const OPTIONAL_HEADER_PE32_PLUS: u16 = 0x20B;
+    const RESOURCE_DIRECTORY_INDEX: u32 = 2;
+    const SECTION_HEADER_SIZE: usize = 40;
+    
+    let truncated = || "Executable headers are truncated".to_string();
+    
+    if headers.get(..2) != Some(b"MZ") {
+        return Err("Not an executable".to_string());
+    }
+    let pe = read_u32(headers, 0x3C).ok_or_else(truncated)? as usize;
+    if headers.get(pe..pe + 4) != Some(b"PE\0\0") {
+        return Err("Not a PE executable".to_string());
+    }
+    let section_count = read_u16(headers, pe + 6).ok_or_else(truncated)? as usize;
+    let optional_size = read_u16(headers, pe + 20).ok_or_else(truncated)? as usize;
+    let optional = pe + 24;
+    let directories = match read_u16(headers, optional) {
+        Some(OPTIONAL_HEADER_PE32) => optional + 96,
+        Some(OPTIONAL_HEADER_PE32_PLUS) => optional + 112,
+        _ => return Err("Unknown optional header".to_string()),
+    };
+    
+    let directory_count = read_u32(headers, directories - 4).ok_or_else(truncated)?;
+    let resource_rva = (directory_count > RESOURCE_DIRECTORY_INDEX)
+        .then(|| read_u32(headers, directories + RESOURCE_DIRECTORY_INDEX as usize * 8))
+        .flatten()
+        .filter(|&rva| rva != 0)
+        .ok_or_else(|| "Executable has no resources".to_string())?;
+    
+    (0..section_count)
+        .map(|section| optional + optional_size + section * SECTION_HEADER_SIZE)
+        .find_map(|header| {
+            let virtual_size = read_u32(headers, header + 8)?;
+            let virtual_address = read_u32(headers, header + 12)?;
+            let raw_size = read_u32(headers, header + 16)?;
+            let raw_offset = read_u32(headers, header + 20)?;
+            (virtual_address..virtual_address.saturating_add(virtual_size.max(raw_size)))
+                .contains(&resource_rva)
+                .then_some((raw_offset as u64, raw_size as usize, virtual_address, resource_rva))
+        })
+        .ok_or_else(|| "Resource section not found".to_string())
+}
+
+#[cfg(any(target_os = "windows", test))]
+fn parse_version_resource(section: &[u8], section_rva: u32, resource_rva: u32) -> Result<VersionInfo, String> {
+    const RT_VERSION: u32 = 16;
+    const RESOURCE_SUBDIRECTORY: u32 = 0x8000_0000;
+    
+    let malformed = || "Resource directory is malformed".to_string();
+    
+    let root = resource_rva.checked_sub(section_rva).ok_or_else(malformed)? as usize;
+    let entry_offset = |directory: usize, id: Option<u32>| {
+        let count = read_u16(section, directory + 12)? as usize + read_u16(section, directory + 14)? as usize;
+        (0..count)
+            .map(|entry| directory + 16 + entry * 8)
+            .find(|&entry| id.is_none_or(|id| read_u32(section, entry) == Some(id)))
+            .and_then(|entry| read_u32(section, entry + 4))
+    };
+    
+    let mut offset = entry_offset(root, Some(RT_VERSION)).ok_or_else(|| "Executable has no version resource".to_string())?;
+    for _ in 0..2 {
+        if offset & RESOURCE_SUBDIRECTORY == 0 {
+            break;
+        }
+        offset = entry_offset(root + (offset & !RESOURCE_SUBDIRECTORY) as usize, None).ok_or_else(malformed)?;
+    }
+    if offset & RESOURCE_SUBDIRECTORY != 0 {
+        return Err(malformed());
+    }
+    
+    let data_entry = root + offset as usize;
+    let data_rva = read_u32(section, data_entry).ok_or_else(malformed)?;
+    let size = read_u32(section, data_entry + 4).ok_or_else(malformed)? as usize;
+    let start = data_rva.checked_sub(section_rva).ok_or_else(malformed)? as usize;
+    let data = section.get(start..start + size).ok_or_else(malformed)?;
+    
+    let mut info = VersionInfo::default();
+    collect_version_strings(data, &mut info);
+    Ok(info)
+}
+
+#[cfg(any(target_os = "windows", test))]
+fn collect_version_strings(data: &[u8], info: &mut VersionInfo) {
+    for (key, value, children) in version_blocks(data) {
+        let field = match key.as_str() {
+            "ProductName" => &mut info.product_name,
+            "FileDescription" => &mut info.file_description,
+            "CompanyName" => &mut info.company_name,
+            _ => {
+                collect_version_strings(children, info);
+                continue;
+            }
+        };
+        if field.is_empty() {
+            *field = decode_utf16(value).trim().to_string();
+        }
+    }
+}
+
+#[cfg(any(target_os = "windows", test))]
+fn version_blocks(data: &[u8]) -> Vec<(String, &[u8], &[u8])> {
+    const TEXT_VALUE: u16 = 1;
+    
+    let align = |offset: usize| (offset + 3) & !3;
+    let mut blocks = Vec::new();
+    let mut offset = 0;
+    
+    while let Some(length) = read_u16(data, offset).map(usize::from).filter(|&length| length >= 6) {
+        let Some(block) = data.get(offset..offset + length) else {
+            break;
+        };
+        let value_length = read_u16(block, 2).unwrap_or_default() as usize;
+        let value_size = if read_u16(block, 4) == Some(TEXT_VALUE) { value_length * 2 } else { value_length };
+        
+        let key_units = block[6..].chunks_exact(2)
+            .position(|unit| unit == [0, 0])
+            .unwrap_or((block.len() - 6) / 2);
+        let key = decode_utf16(&block[6..6 + key_units * 2]);
+        
+        let value_start = align(6 + key_units * 2 + 2).min(block.len());
+        let value_end = (value_start + value_size).min(block.len());
+        let children_start = align(value_end).min(block.len());
+        blocks.push((key, &block[value_start..value_end], &block[children_start..]));
+        
+        offset = align(offset + length);
+    }
+    
+    blocks
+}
+
+#[cfg(target_os = "windows")]
+fn load_app_filters() -> Vec<String> {
+    let config_path = get_config_dir().join("app_filters.conf");
+    if !config_path.exists() {
+        create_default_app_filters(&config_path);
+    }
+    
+    fs::read_to_string(&config_path)
+        .map(|content| {
+            content.lines()
+                .map(|line| line.trim())
+                .filter(|line| !line.is_empty() && !line.starts_with('#'))
+                .map(|line| line.to_lowercase())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+#[cfg(target_os = "windows")]
+fn create_default_app_filters(config_path: &PathBuf) {
+    let default_filters = r#"# Flint App Filters
+# Start Menu shortcuts and Program Files executables matching any pattern below are left out of app results
+# Patterns are checked against the shortcut name, the executable name and its file description
+# * matches any text, ? matches a single character, case is ignored
+
+unins*
+*uninstall*
+*updater*
+update
+*crashpad*
+*crash*report*
+*helper
+setup
+installer
+elevation_service
+"#;
+
+    if let Some(parent) = config_path.parent() {
+        let _ = fs::create_dir_all(parent);
+    }
+    let _ = fs::write(config_path, default_filters);
+}
+
+#[cfg(any(target_os = "windows", test))]
+fn is_filtered_app(filters: &[String], names: &[&str]) -> bool {
+    names.iter()
+        .filter(|name| !name.is_empty())
+        .map(|name| name.to_lowercase())
+        .any(|name| filters.iter().any(|pattern| wildcard_match(pattern, &name)))
+}
+
+#[cfg(any(target_os = "windows", test))]
+fn wildcard_match(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let text: Vec<char> = text.chars().collect();
+    let (mut p, mut t) = (0, 0);
+    let mut backtrack = None;
+    
+    while t < text.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                backtrack = Some((p, t));
+                p += 1;
+            }
+            Some(&c) if c == '?' || c == text[t] => {
+                p += 1;
+                t += 1;
+            }
+            _ => match backtrack {
+                Some((star, matched)) => {
+                    backtrack = Some((star, matched + 1));
+                    p = star + 1;
+                    t = matched + 1;
+                }
+                None => return false,
+            },
+        }
+    }
+    
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+fn desktop_dirs() -> Vec<PathBuf> {
+    dirs::desktop_dir()
+        .or_else(|| dirs::home_dir().map(|home| home.join("Desktop")))
+        .into_iter()
+        .chain(std::env::var("PUBLIC").ok().map(|public| PathBuf::from(public).join("Desktop")))
+        .filter(|dir| dir.is_dir())
+        .collect()
+}
+
+fn scan_desktop_shortcuts() -> Vec<AppEntry> {
+    let mut apps = Vec::new();
+    
+    for dir in desktop_dirs() {
+        let Ok(entries) = fs::read_dir(&dir) else {
+            continue;
+        };
+        for path in entries.flatten().map(|entry| entry.path()) {
+            if path.extension().and_then(|e| e.to_str()).is_some_and(|e| e.eq_ignore_ascii_case("url")) {
+                apps.extend(internet_shortcut_app(&path));
+            }
+        }
+    }
+    
+    debug!(shortcuts = apps.len(), "scanned desktop internet shortcuts");
+    apps
+}
+
+fn internet_shortcut_app(path: &std::path::Path) -> Option<AppEntry> {
+    let content = fs::read(path).ok()?;
+    let shortcut = parse_internet_shortcut(&String::from_utf8_lossy(&content)).filter(|shortcut| is_internet_shortcut_url(&shortcut.url))?;
+    
+    Some(AppEntry {
+        name: path.file_stem()?.to_str()?.to_string(),
+        desktop_id: path.display().to_string(),
+        exec_command: shortcut.url,
+        match_indices: Vec::new(),
+        generic_name: String::new(),
+        keywords: Vec::new(),
+        categories: Vec::new(),
+        comment: String::new(),
+        source: AppSource::Link,
+        actions: Vec::new(),
+        terminal: false,
+        icon: shortcut.icon_file,
+        working_dir: None,
+        desktop_file: Some(path.to_path_buf()),
+    })
+}
+
+const INTERNET_SHORTCUT_SCHEMES: [&str; 5] = ["http", "https", "mailto", "ftp", "steam"];
+
+fn is_internet_shortcut_url(url: &str) -> bool {
+    url.split_once(':')
+        .is_some_and(|(scheme, _)| INTERNET_SHORTCUT_SCHEMES.iter().any(|allowed| scheme.eq_ignore_ascii_case(allowed)))
+}
+
+#[derive(Debug, Default, PartialEq)]
+struct InternetShortcut {
+    url: String,
+    icon_file: String,
+}
+
+fn parse_internet_shortcut(content: &str) -> Option<InternetShortcut> {
+    let mut shortcut = InternetShortcut::default();
+    let mut in_section = false;
+    
+    for line in content.trim_start_matches('\u{feff}').lines() {
+        let line = line.trim();
+        if line.starts_with('[') && line.ends_with(']') {
+            in_section = line[1..line.len() - 1].trim().eq_ignore_ascii_case("InternetShortcut");
+            continue;
+        }
+        if !in_section {
+            continue;
+        }
+        
+        let Some((key, value)) = line.split_once('=') else {
+            continue;
+        };
+        let value = value.trim().to_string();
+        match key.trim().to_ascii_lowercase().as_str() {
+            "url" => shortcut.url = value,
+            "iconfile" => shortcut.icon_file = value,
+            _ => {}
+        }
+    }
+    
+    (!shortcut.url.is_empty()).then_some(shortcut)
+}
+
+#[derive(Clone, Debug, PartialEq)]
+struct SteamGame {
+    app_id: u32,
+    name: String,
+}
+
+const STEAM_STATE_FULLY_INSTALLED: u32 = 4;
+const STEAM_TOOL_APP_IDS: [u32; 1] = [228980];
+const STEAM_TOOL_PREFIXES: [&str; 3] = ["Proton ", "Steam Linux Runtime", "Steamworks "];
+
+#[cfg(target_os = "windows")]
+fn steam_roots() -> Vec<PathBuf> {
+    let mut roots: Vec<PathBuf> = ["PROGRAMFILES(X86)", "PROGRAMFILES"].into_iter()
+        .filter_map(|var| std::env::var(var).ok())
+        .map(|base| PathBuf::from(base).join("Steam"))
+        .filter(|dir| dir.is_dir())
+        .collect();
+    roots.dedup();
+    roots
+}
+
+#[cfg(not(target_os = "windows"))]
+fn steam_roots() -> Vec<PathBuf> {
+    let Some(home) = dirs::home_dir() else {
+        return Vec::new();
+    };
+    
+    let mut roots = Vec::new();
+    for dir in [
+        home.join(".steam/steam"),
+        home.join(".steam/root"),
+        home.join(".local/share/Steam"),
+        home.join(".var/app/com.valvesoftware.Steam/.local/share/Steam"),
+    ] {
+        if let Ok(root) = dir.canonicalize() {
+            if root.is_dir() && !roots.contains(&root) {
+                roots.push(root);
+            }
+        }
+    }
+    roots
+}
+
+fn scan_steam_games() -> Vec<SteamGame> {
+    let mut libraries = Vec::new();
+    for root in steam_roots() {
+        libraries.push(root.clone());
+        for file in [root.join("steamapps/libraryfolders.vdf"), root.join("config/libraryfolders.vdf")] {
+            if let Ok(content) = fs::read_to_string(&file) {
+                libraries.extend(steam_library_paths(&content));
+                break;
+            }
+        }
+    }
+    
+    let mut games: Vec<SteamGame> = Vec::new();
+    let mut seen_libraries = std::collections::HashSet::new();
+    for library in libraries {
+        let library = library.canonicalize().unwrap_or(library);
+        if !seen_libraries.insert(library.clone()) {
+            continue;
+        }
+        
+        let Ok(entries) = fs::read_dir(library.join("steamapps")) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            let path = entry.path();
+            let is_manifest = path.file_name()
+                .and_then(|name| name.to_str())
+                .is_some_and(|name| name.starts_with("appmanifest_") && name.ends_with(".acf"));
+            if !is_manifest {
+                continue;
+            }
+            
+            match fs::read_to_string(&path).map(|content| parse_steam_manifest(&content)) {
+                Ok(Some(game)) if !games.iter().any(|known| known.app_id == game.app_id) => games.push(game),
+                Ok(_) => {}
+                Err(e) => debug!(path = %path.display(), "skipping Steam manifest: {}", e),
+            }
+        }
+    }
+    
+    games.sort_by(|a, b| a.name.cmp(&b.name));
+    debug!(games = games.len(), libraries = seen_libraries.len(), "scanned Steam libraries");
+    games
+}
+
+fn steam_library_paths(content: &str) -> Vec<PathBuf> {
+    let entries = parse_vdf(content);
+    let Some(VdfValue::Object(folders)) = vdf_get(&entries, "libraryfolders") else {
+        return Vec::new();
+    };
+    
+    folders.iter()
+        .filter(|(key, _)| key.chars().all(|c| c.is_ascii_digit()))
+        .filter_map(|(_, value)| match value {
+            VdfValue::Text(path) => Some(PathBuf::from(path)),
+            VdfValue::Object(folder) => vdf_text(folder, "path").map(PathBuf::from),
+        })
+        .collect()
+}
+
+fn parse_steam_manifest(content: &str) -> Option<SteamGame> {
+    let entries = parse_vdf(content);
+    let Some(VdfValue::Object(state)) = vdf_get(&entries, "AppState") else {
+        return None;
+    };
+    
+    let app_id: u32 = vdf_text(state, "appid")?.parse().ok()?;
+    let name = vdf_text(state, "name")?.trim().to_string();
+    let flags: u32 = vdf_text(state, "StateFlags").and_then(|flags| flags.parse().ok()).unwrap_or_default();
+    
+    let is_tool = STEAM_TOOL_APP_IDS.contains(&app_id) || STEAM_TOOL_PREFIXES.iter().any(|prefix| name.starts_with(prefix));
+    if name.is_empty() || is_tool || flags & STEAM_STATE_FULLY_INSTALLED == 0 {
+        return None;
+    }
+    
+    Some(SteamGame { app_id, name })
+}
+
+#[derive(Debug, PartialEq)]
+enum VdfValue {
+    Text(String),
+    Object(Vec<(String, VdfValue)>),
+}
+
+#[derive(Debug, PartialEq)]
+enum VdfToken {
+    Text(String),
+    Open,
+    Close,
+}
+
+fn vdf_get<'a>(entries: &'a [(String, VdfValue)], key: &str) -> Option<&'a VdfValue> {
+    entries.iter()
+        .find(|(name, _)| name.eq_ignore_ascii_case(key))
+        .map(|(_, value)| value)
+}
+
+fn vdf_text<'a>(entries: &'a [(String, VdfValue)], key: &str) -> Option<&'a str> {
+    match vdf_get(entries, key)? {
+        VdfValue::Text(text) => Some(text),
+        VdfValue::Object(_) => None,
+    }
+}
+
+fn parse_vdf(content: &str) -> Vec<(String, VdfValue)> {
+    parse_vdf_object(&mut vdf_tokens(content).into_iter())
+}
+
+fn parse_vdf_object(tokens: &mut impl Iterator<Item = VdfToken>) -> Vec<(String, VdfValue)> {
+    let mut entries = Vec::new();
+    
+    while let Some(VdfToken::Text(key)) = tokens.next() {
+        match tokens.next() {
+            Some(VdfToken::Text(value)) => entries.push((key, VdfValue::Text(value))),
+            Some(VdfToken::Open) => entries.push((key, VdfValue::Object(parse_vdf_object(tokens)))),
+            _ => break,
+        }
+    }
+    
+    entries
+}
+
+fn vdf_tokens(content: &str) -> Vec<VdfToken> {
+    let mut tokens = Vec::new();
+    let mut chars = content.trim_start_matches('\u{feff}').chars().peekable();
+    
+    while let Some(c) = chars.next() {
+        match c {
+            '{' => tokens.push(VdfToken::Open),
+            '}' => tokens.push(VdfToken::Close),
+            '"' => {
+                let mut text = String::new();
+                while let Some(c) = chars.next() {
+                    match c {
+                        '"' => break,
+                        '\\' => match chars.next() {
+                            Some('n') => text.push('\n'),
+                            Some('t') => text.push('\t'),
+                            Some(escaped @ ('\\' | '"')) => text.push(escaped),
+                            Some(other) => {
+                                text.push('\\');
+                                text.push(other);
+                            }
+                            None => {}
+                        },
+                        c => text.push(c),
+                    }
+                }
+                tokens.push(VdfToken::Text(text));
+            }
+            '/' if chars.peek() == Some(&'/') => {
+                for c in chars.by_ref() {
+                    if c == '\n' {
+                        break;
+                    }
+                }
+            }
+            '[' => {
+                for c in chars.by_ref() {
+                    if c == ']' {
+                        break;
+                    }
+                }
+            }
+            c if c.is_whitespace() => {}
+            c => {
+                let mut text = String::from(c);
+                while let Some(&next) = chars.peek() {
+                    if next.is_whitespace() || matches!(next, '{' | '}' | '"') {
+                        break;
+                    }
+                    text.push(next);
+                    chars.next();
+                }
+                tokens.push(VdfToken::Text(text));
+            }
+        }
+    }
+    
+    tokens
+}
+
+#[cfg(not(target_os = "windows"))]
+fn scan_linux_apps() -> Vec<AppEntry> {
+    let mut apps = Vec::new();
+    let mut seen_ids = std::collections::HashSet::new();
+    
+    for dir in linux_application_dirs() {
+        let source = app_source_for_dir(&dir);
+        let mut desktop_files = Vec::new();
+        collect_desktop_files(&dir, &dir, &mut desktop_files);
+        debug!(dir = %dir.display(), desktop_files = desktop_files.len(), "scanned application directory");
+        
+        for (desktop_id, path) in desktop_files {
+            if !seen_ids.insert(desktop_id.clone()) {
+                continue;
+            }
+            
+            let Some(entry) = fs::read_to_string(&path).ok().and_then(|content| parse_desktop_entry(&content)) else {
+                continue;
+            };
+            if entry.hidden || entry.no_display || entry.exec.is_empty() {
+                continue;
+            }
+            
+            apps.push(AppEntry {
+                name: entry.name,
+                generic_name: entry.generic_name,
+                keywords: entry.keywords,
+                categories: entry.categories,
+                comment: entry.comment,
+                desktop_id,
+                exec_command: entry.exec,
+                match_indices: Vec::new(),
+                source: if entry.flatpak { AppSource::Flatpak } else if entry.snap { AppSource::Snap } else { source },
+                actions: entry.actions,
+                terminal: entry.terminal,
+                icon: entry.icon,
+                working_dir: Some(PathBuf::from(entry.path)).filter(|dir| !dir.as_os_str().is_empty()),
+                desktop_file: Some(path),
+            });
+        }
+    }
+    apps.extend(scan_desktop_shortcuts());
+    
+    let common_apps = [
+        ("Firefox", "firefox"),
+        ("Terminal", "gnome-terminal"),
+        ("Files", "nautilus"),
+        ("Text Editor", "gedit"),
+        ("Calculator", "gnome-calculator"),
+        ("Settings", "gnome-control-center"),
+    ];
+    
+    let known_execs: std::collections::HashSet<String> = apps.iter()
+        .filter_map(|app| tokenize_exec(&app.exec_command).into_iter().next())
+        .filter_map(|program| std::path::Path::new(&program).file_name().and_then(|n| n.to_str()).map(str::to_string))
+        .collect();
+    
+    for (name, exec) in common_apps {
+        if known_execs.contains(exec) || apps.iter().any(|app| app.name == name) {
+            continue;
+        }
+        apps.push(AppEntry {
+            name: name.to_string(),
+            desktop_id: name.to_string(),
+            exec_command: exec.to_string(),
+            match_indices: Vec::new(),
+            generic_name: String::new(),
+            keywords: Vec::new(),
+            categories: Vec::new(),
+            comment: String::new(),
+            source: AppSource::Native,
+            actions: Vec::new(),
+            terminal: false,
+            icon: String::new(),
+            working_dir: None,
+            desktop_file: None,
+        });
+    }
+    
+    apps.sort_by(|a, b| a.name.cmp(&b.name));
+    apps
+}
+
+#[cfg(not(target_os = "windows"))]
+fn linux_application_dirs() -> Vec<PathBuf> {
+    let home = dirs::home_dir().unwrap_or_default();
+    
+    let data_home = std::env::var_os("XDG_DATA_HOME")
+        .map(PathBuf::from)
+        .filter(|p| p.is_absolute())
+        .unwrap_or_else(|| home.join(".local/share"));
+    
+    let data_dirs = std::env::var("XDG_DATA_DIRS")
+        .ok()
+        .filter(|value| !value.trim().is_empty())
+        .unwrap_or_else(|| "/usr/local/share:/usr/share".to_string());
+    
+    let mut dirs = vec![data_home.join("applications")];
+    dirs.extend(
+        data_dirs.split(':')
+            .map(PathBuf::from)
+            .filter(|p| p.is_absolute())
+            .map(|p| p.join("applications"))
+    );
+    dirs.push(home.join(".local/share/flatpak/exports/share/applications"));
+    dirs.push(PathBuf::from("/var/lib/flatpak/exports/share/applications"));
+    dirs.push(PathBuf::from("/var/lib/snapd/desktop/applications"));
+    
+    let mut unique = Vec::new();
+    for dir in dirs {
+        if !unique.contains(&dir) {
+            unique.push(dir);
+        }
+    }
+    unique
+}
+
+#[cfg(not(target_os = "windows"))]
+fn app_source_for_dir(dir: &std::path::Path) -> AppSource {
+    let dir = dir.to_string_lossy();
+    if dir.contains("/flatpak/exports/") {
+        AppSource::Flatpak
+    } else if dir.starts_with("/var/lib/snapd/") {
+        AppSource::Snap
+    } else {
+        AppSource::Native
+    }
+}
+
+#[cfg(not(target_os = "windows"))]
+fn collect_desktop_files(root: &std::path::Path, dir: &std::path::Path, files: &mut Vec<(String, PathBuf)>) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+    
+    let mut paths: Vec<PathBuf> = entries.flatten().map(|entry| entry.path()).collect();
+    paths.sort();
+    
+    for path in paths {
+        if path.is_dir() {
+            collect_desktop_files(root, &path, files);
+        } else if path.extension().and_then(|e| e.to_str()) == Some("desktop") {
+            if let Ok(relative) = path.strip_prefix(root) {
+                let desktop_id = relative.to_string_lossy().replace(['/', '\\'], "-");
+                files.push((desktop_id, path));
+            }
+        }
+    }
+}
+
+#[cfg(not(target_os = "windows"))]
+fn parse_desktop_entry(content: &str) -> Option<DesktopEntry> {
+    let mut entry = DesktopEntry::default();
+    let mut group = String::new();
+    let mut is_application = false;
+    let mut action_ids = Vec::new();
+    let mut action_groups: std::collections::HashMap<String, DesktopAction> = std::collections::HashMap::new();
+    
+    for line in content.lines() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        if line.starts_with('[') && line.ends_with(']') {
+            group = line[1..line.len() - 1].to_string();
+            continue;
+        }
+        
+        let Some((key, value)) = line.split_once('=') else {
+            continue;
+        };
+        let value = value.trim();
+        
+        if let Some(action_id) = group.strip_prefix("Desktop Action ") {
+            let action = action_groups.entry(action_id.trim().to_string()).or_insert_with(|| DesktopAction {
+                name: String::new(),
+                exec: String::new(),
+            });
+            match key.trim() {
+                "Name" => action.name = unescape_desktop_value(value),
+                "Exec" => action.exec = unescape_desktop_value(value),
+                _ => {}
+            }
+            continue;
+        }
+        if group != "Desktop Entry" {
+            continue;
+        }
+        
+        match key.trim() {
+            "Actions" => {
+                action_ids = value.split(';')
+                    .map(|id| id.trim().to_string())
+                    .filter(|id| !id.is_empty())
+                    .collect();
+            }
+            "Type" => is_application = value == "Application",
+            "Name" => entry.name = unescape_desktop_value(value),
+            "GenericName" => entry.generic_name = unescape_desktop_value(value),
+            "Keywords" => entry.keywords = split_desktop_list(value),
+            "Categories" => entry.categories = split_desktop_list(value),
+            "Comment" => entry.comment = unescape_desktop_value(value),
+            "Exec" => entry.exec = unescape_desktop_value(value),
+            "Icon" => entry.icon = unescape_desktop_value(value),
+            "Path" => entry.path = unescape_desktop_value(value),
+            "Hidden" => entry.hidden = value == "true",
+            "NoDisplay" => entry.no_display = value == "true",
+            "Terminal" => entry.terminal = value == "true",
+            "X-Flatpak" => entry.flatpak = true,
+            "X-SnapInstanceName" => entry.snap = true,
+            _ => {}
+        }
+    }
+    
+    entry.actions = action_ids.iter()
+        .filter_map(|id| action_groups.remove(id))
+        .filter(|action| !action.name.is_empty() && !action.exec.is_empty())
+        .collect();
+    
+    if is_application && !entry.name.is_empty() {
+        Some(entry)
+    } else {
+        None
+    }
+}
+
+#[cfg(not(target_os = "windows"))]
+fn split_desktop_list(value: &str) -> Vec<String> {
+    value.split(';')
+        .map(|item| unescape_desktop_value(item.trim()))
+        .filter(|item| !item.is_empty())
+        .collect()
+}
+
+#[cfg(not(target_os = "windows"))]
+fn unescape_desktop_value(value: &str) -> String {
+    let mut unescaped = String::with_capacity(value.len());
+    let mut chars = value.chars();
+    
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            unescaped.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('s') => unescaped.push(' '),
+            Some('n') => unescaped.push('\n'),
+            Some('t') => unescaped.push('\t'),
+            Some('r') => unescaped.push('\r'),
+            Some('\\') => unescaped.push('\\'),
+            Some(other) => {
+                unescaped.push('\\');
+                unescaped.push(other);
+            }
+            None => unescaped.push('\\'),
+        }
+    }
+    
+    unescaped
+}
+
+fn tokenize_exec(exec: &str) -> Vec<String> {
+    let mut args = Vec::new();
+    let mut current = String::new();
+    let mut in_arg = false;
+    let mut quoted = false;
+    let mut chars = exec.chars().peekable();
+    
+    while let Some(c) = chars.next() {
+        match c {
+            '"' => {
+                quoted = !quoted;
+                in_arg = true;
+            }
+            '\\' if quoted && matches!(chars.peek(), Some('"' | '`' | '$' | '\\')) => {
+                current.extend(chars.next());
+            }
+            c if c.is_whitespace() && !quoted => {
+                if in_arg {
+                    args.push(std::mem::take(&mut current));
+                    in_arg = false;
+                }
+            }
+            c => {
+                current.push(c);
+                in_arg = true;
+            }
+        }
+    }
+    if in_arg {
+        args.push(current);
+    }
+    
+    args
+}
+
+fn exec_argv(app: &AppEntry, exec: &str) -> Vec<String> {
+    let mut argv = Vec::new();
+    
+    for arg in tokenize_exec(exec) {
+        match arg.as_str() {
+            "%f" | "%F" | "%u" | "%U" | "%d" | "%D" | "%n" | "%N" | "%v" | "%m" => continue,
+            "%i" => {
+                if !app.icon.is_empty() {
+                    argv.push("--icon".to_string());
+                    argv.push(app.icon.clone());
+                }
+                continue;
+            }
+            _ => {}
+        }
+        
+        let mut expanded = String::new();
+        let mut chars = arg.chars();
+        while let Some(c) = chars.next() {
+            if c != '%' {
+                expanded.push(c);
+                continue;
+            }
+            match chars.next() {
+                Some('%') => expanded.push('%'),
+                Some('c') => expanded.push_str(&app.name),
+                Some('k') => {
+                    if let Some(desktop_file) = &app.desktop_file {
+                        expanded.push_str(&desktop_file.to_string_lossy());
+                    }
+                }
+                _ => {}
+            }
+        }
+        argv.push(expanded);
+    }
+    
+    argv
+}
+
+fn load_environment_overrides() -> Vec<(String, String)> {
+    let config_path = get_config_dir().join("environment.conf");
+    if !config_path.exists() {
+        create_default_environment(&config_path);
+    }
+    
+    fs::read_to_string(&config_path)
+        .map(|content| {
+            content.lines()
+                .map(|line| line.trim())
+                .filter(|line| !line.starts_with('#'))
+                .filter_map(|line| line.split_once('='))
+                .map(|(key, value)| (key.trim().to_string(), value.trim().to_string()))
+                .filter(|(key, _)| !key.is_empty())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn create_default_environment(config_path: &PathBuf) {
+    let default_environment = r#"# Flint Launch Environment
+# Variables set for every application launched from Flint
+# Format: NAME=value (an empty value removes the variable)
+
+# GTK_THEME=Adwaita:dark
+# QT_QPA_PLATFORM=wayland
+"#;
+
+    if let Some(parent) = config_path.parent() {
+        let _ = fs::create_dir_all(parent);
+    }
+    let _ = fs::write(config_path, default_environment);
+}
+
+fn apply_launch_environment(command: &mut Command, app: &AppEntry) {
+    if let Some(dir) = app.working_dir.as_ref().filter(|dir| dir.is_dir()) {
+        command.current_dir(dir);
+    }
+    
+    for (key, value) in load_environment_overrides() {
+        if value.is_empty() {
+            command.env_remove(key);
+        } else {
+            command.env(key, value);
+        }
+    }
+}
+
+#[cfg(target_os = "windows")]
+fn get_config_dir() -> PathBuf {
+    dirs::config_dir()
+        .map(|p| p.join("Flint"))
+        .unwrap_or_else(|| PathBuf::from("C:\\ProgramData\\Flint"))
+}
+
+#[cfg(not(target_os = "windows"))]
+fn get_config_dir() -> PathBuf {
+    dirs::config_dir()
+        .map(|p| p.join("flint"))
+        .unwrap_or_else(|| PathBuf::from("~/.config/flint"))
+}
+
+fn get_log_dir() -> PathBuf {
+    dirs::state_dir()
+        .map(|dir| dir.join("flint").join("logs"))
+        .unwrap_or_else(|| get_config_dir().join("logs"))
+}
+
+fn init_logging(verbose: bool) -> Option<tracing_appender::non_blocking::WorkerGuard> {
+    use tracing_subscriber::prelude::*;
+    
+    let level = if verbose { "debug" } else { "info" };
+    let filter = tracing_subscriber::EnvFilter::try_from_env("FLINT_LOG")
+        .unwrap_or_else(|_| tracing_subscriber::EnvFilter::new(format!("warn,{}={}", env!("CARGO_CRATE_NAME"), level)));
+    
+    let _ = fs::create_dir_all(get_log_dir());
+    let appender = tracing_appender::rolling::Builder::new()
+        .rotation(tracing_appender::rolling::Rotation::DAILY)
+        .filename_prefix("flint")
+        .filename_suffix("log")
+        .max_log_files(7)
+        .build(get_log_dir());
+    
+    let (file_layer, guard) = match appender {
+        Ok(appender) => {
+            let (writer, guard) = tracing_appender::non_blocking(appender);
+            (Some(tracing_subscriber::fmt::layer().with_ansi(false).with_writer(writer)), Some(guard))
+        }
+        Err(e) => {
+            eprintln!("Failed to open log file in {}: {}", get_log_dir().display(), e);
+            (None, None)
+        }
+    };
+    let stderr_layer = verbose.then(|| tracing_subscriber::fmt::layer().with_writer(std::io::stderr));
+    
+    let _ = tracing_subscriber::registry()
+        .with(filter)
+        .with(file_layer)
+        .with(stderr_layer)
+        .try_init();
+    
+    guard
+}
+
+fn current_log_file() -> Option<PathBuf> {
+    fs::read_dir(get_log_dir())
+        .ok()?
+        .flatten()
+        .filter(|entry| entry.file_name().to_string_lossy().starts_with("flint"))
+        .max_by_key(|entry| entry.metadata().and_then(|m| m.modified()).ok())
+        .map(|entry| entry.path())
+}
+
+fn open_log() -> Result<(), String> {
+    let log_file = current_log_file()
+        .ok_or_else(|| format!("No log file in {}", get_log_dir().display()))?;
+    open_file(&log_file)
+}
+
+#[cfg(target_os = "windows")]
+const CREATE_NO_WINDOW: u32 = 0x08000000;
+
+#[cfg(target_os = "windows")]
+const CREATE_NEW_CONSOLE: u32 = 0x00000010;
+
+#[cfg(target_os = "windows")]
+const CREATE_NEW_PROCESS_GROUP: u32 = 0x00000200;
+
+#[cfg(target_os = "windows")]
+const DETACHED_PROCESS: u32 = 0x00000008;
+
+#[cfg(target_os = "windows")]
+fn default_terminal() -> &'static str {
+    "cmd"
+}
+
+#[cfg(not(target_os = "windows"))]
+fn default_terminal() -> &'static str {
+    "auto"
+}
+
+#[cfg(target_os = "windows")]
+const KNOWN_TERMINALS: [(&str, &str); 2] = [
+    ("wt", "cmd /K {}"),
+    ("cmd", "/K {}"),
+];
+
+#[cfg(not(target_os = "windows"))]
+const KNOWN_TERMINALS: [(&str, &str); 9] = [
+    ("x-terminal-emulator", "-e {}"),
+    ("gnome-terminal", "-- {}"),
+    ("kitty", "{}"),
+    ("alacritty", "-e {}"),
+    ("wezterm", "start -- {}"),
+    ("foot", "{}"),
+    ("konsole", "-e {}"),
+    ("xfce4-terminal", "-x {}"),
+    ("xterm", "-e {}"),
+];
+
+#[cfg(target_os = "windows")]
+const DEFAULT_TERMINAL_ARGS: &str = "/K {}";
+
+#[cfg(not(target_os = "windows"))]
+const DEFAULT_TERMINAL_ARGS: &str = "-e {}";
+
+fn detect_terminal() -> Option<String> {
+    static DETECTED: std::sync::OnceLock<Option<String>> = std::sync::OnceLock::new();
+    DETECTED.get_or_init(find_terminal).clone()
+}
+
+fn find_terminal() -> Option<String> {
+    std::env::var("TERMINAL").ok()
+        .filter(|terminal| !terminal.is_empty() && is_on_path(terminal))
+        .or_else(|| {
+            KNOWN_TERMINALS.iter()
+                .map(|(name, _)| name.to_string())
+                .find(|name| is_on_path(name))
+        })
+        .inspect(|terminal| debug!(terminal = %terminal, "detected terminal emulator"))
+}
+
+fn terminal_args_template(terminal: &str) -> &'static str {
+    let name = std::path::Path::new(terminal)
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .unwrap_or(terminal);
+    
+    KNOWN_TERMINALS.iter()
+        .find(|(known, _)| *known == name)
+        .map(|(_, args)| *args)
+        .unwrap_or(DEFAULT_TERMINAL_ARGS)
+}
+
+fn is_on_path(name: &str) -> bool {
+    let Some(path_var) = std::env::var_os("PATH") else {
+        return false;
+    };
+    
+    std::env::split_paths(&path_var).any(|dir| {
+        ["", ".exe"].iter().any(|ext| {
+            let path = dir.join(format!("{}{}", name, ext));
+            path.is_file() && executable_name(&path).is_some()
+        })
+    })
+}
+
+fn create_default_theme(theme_path: &PathBuf) {
+    let default_theme = r#"# Flint Theme Configuration
+# Dark Theme
+
+# Main window colors
+background=#2d2d30
+text_color=#ffffff
+selection_bg=#0078d4
+selection_text=#ffffff
+border_color=#3e3e42
+highlight_color=#0078d4
+
+# Font settings
+font_size=16
+font_family=Segoe UI
+
+# Border radius
+border_radius=2
+"#;
+    
+    if let Some(parent) = theme_path.parent() {
+        let _ = fs::create_dir_all(parent);
+    }
+    let _ = fs::write(theme_path, default_theme);
+}
+
+fn main() -> eframe::Result<()> {
+    let verbose = std::env::args().any(|arg| arg == "--verbose");
+    let args: Vec<String> = std::env::args().filter(|arg| arg != "--verbose").collect();
+    let log_guard = init_logging(verbose);
+    info!(version = env!("CARGO_PKG_VERSION"), args = ?&args[1..], "starting Flint");
+    
+    let run_in_tray = args.len() > 1 && args[1] == "--tray";
+    
+    if run_in_tray {
+        println!("Flint Launcher running in system tray...");
+        println!("Config location: {}", get_config_dir().display());
+        println!("Right-click the tray icon to access options.");
+        
+        start_tray_thread();
+        
+        loop {
+            thread::sleep(Duration::from_secs(10));
+        }
+    }
+    
+    let mode = if args.len() > 1 && args[1] == "settings" {
+        AppMode::Settings
+    } else {
+        AppMode::Launcher
+    };
+
+    let mut app = match FlintApp::new() {
+        Ok(mut app) => {
+            app.app_mode = mode;
+            app
+        }
+        Err(e) => {
+            error!("{}", e);
+            eprintln!("{}", e);
+            drop(log_guard);
+            std::process::exit(1);
+        }
+    };
+    
+    let (title, width, height) = if mode == AppMode::Settings {
+        ("Flint Settings", 550.0, 600.0)
+    } else {
+        ("Flint", 600.0, 50.0)
+    };
+
+    let options = eframe::NativeOptions {
+        viewport: egui::ViewportBuilder::default()
+            .with_inner_size([width, height])
+            .with_decorations(mode == AppMode::Settings)
+            .with_always_on_top()
+            .with_resizable(false)
+            .with_window_level(egui::WindowLevel::AlwaysOnTop)
+            .with_position(egui::pos2(
+                (1920.0 - width) / 2.0,
+                200.0,
+            )),
+        centered: false,
+        ..Default::default()
+    };
+
+    eframe::run_native(
+        title,
+        options,
+        Box::new(move |cc| {
+            cc.egui_ctx.send_viewport_cmd(egui::ViewportCommand::Focus);
+            app.egui_ctx = Some(cc.egui_ctx.clone());
+            if let Some(scripts) = &app.scripts {
+                scripts.set_repaint_context(cc.egui_ctx.clone());
+            }
+            Box::new(app)
+        }),
+    )
+}
 
-        ctx.request_repaint();
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    fn fixed_snippet_config() -> SnippetConfig {
+        SnippetConfig {
+            date_format: "DATE".to_string(),
+            time_format: "TIME".to_string(),
+            ..SnippetConfig::default()
+        }
     }
     
-    fn render_settings(&mut self, ctx: &egui::Context) {
-        let config = self.hotkey_config.lock().ok();
-        if let Some(cfg) = config {
-            self.temp_launcher_key = cfg.launcher_key.clone();
-            self.temp_settings_key = cfg.settings_key.clone();
-            self.temp_enabled = cfg.enabled;
+    fn test_app(name: &str) -> AppEntry {
+        AppEntry {
+            name: name.to_string(),
+            desktop_id: format!("{}.desktop", name.to_lowercase()),
+            exec_command: name.to_lowercase(),
+            match_indices: Vec::new(),
+            generic_name: String::new(),
+            keywords: Vec::new(),
+            categories: Vec::new(),
+            comment: String::new(),
+            source: AppSource::Native,
+            actions: Vec::new(),
+            terminal: false,
+            icon: String::new(),
+            working_dir: None,
+            desktop_file: None,
         }
-        
-        egui::CentralPanel::default().show(ctx, |ui| {
-            ui.heading("⚙️ Flint Launcher Settings");
-            ui.separator();
-            
-            ui.heading("⌨️ Hotkey Configuration");
-            
-            ui.label("Launcher Hotkey:");
-            ui.text_edit_singleline(&mut self.temp_launcher_key);
-            ui.label("Example: Alt+Space, Ctrl+`, Super+Shift+D");
-            
-            ui.separator();
-            
-            ui.label("Settings Hotkey:");
-            ui.text_edit_singleline(&mut self.temp_settings_key);
-            ui.label("Example: Alt+Shift+S");
-            
-            ui.separator();
-            
-            ui.checkbox(&mut self.temp_enabled, "Enable Hotkeys");
-            
-            ui.separator();
-            
-            ui.heading("🎨 Theme Configuration");
-            ui.label(format!("Theme file location: {}", get_config_dir().join("theme.conf").display()));
-            ui.label("Edit this file to change colors, fonts, and appearance.");
-            
-            ui.separator();
-            
-            if self.message_time.elapsed().as_secs() < 4 {
-                ui.colored_label(self.status_color, &self.status_message);
-            }
-            
-            ui.separator();
-            
-            ui.horizontal(|ui| {
-                if ui.button("💾 Save Hotkeys").clicked() {
-                    if let Ok(mut config) = self.hotkey_config.lock() {
-                        config.launcher_key = self.temp_launcher_key.clone();
-                        config.settings_key = self.temp_settings_key.clone();
-                        config.enabled = self.temp_enabled;
-                        config.save();
-                        
-                        self.status_message = "✓ Hotkeys saved! Restart to apply.".to_string();
-                        self.status_color = egui::Color32::GREEN;
-                        self.message_time = Instant::now();
-                    }
-                }
-                
-                if ui.button("🔄 Reset Hotkeys").clicked() {
-                    let defaults = HotkeyConfig::default();
-                    self.temp_launcher_key = defaults.launcher_key.clone();
-                    self.temp_settings_key = defaults.settings_key.clone();
-                    self.temp_enabled = defaults.enabled;
-                    
-                    self.status_message = "Reset to defaults".to_string();
-                    self.status_color = egui::Color32::YELLOW;
-                    self.message_time = Instant::now();
-                }
-                
-                if ui.button("📁 Open Config Folder").clicked() {
-                    let config_dir = get_config_dir();
-                    let _ = open_file(&config_dir);
+    }
+    
+    fn synthetic_apps(count: usize) -> Vec<AppEntry> {
+        let words = [
+            "fire", "fox", "text", "editor", "terminal", "office", "writer", "calc", "image", "viewer",
+            "music", "player", "code", "studio", "mail", "chat", "game", "system", "monitor", "settings",
+            "файл", "café", "微信", "network", "manager",
+        ];
+        (0..count)
+            .map(|i| {
+                let word = |k: usize| words[(i * 7 + k * 13 + i / 3) % words.len()];
+                AppEntry {
+                    name: format!("{} {} {}", word(0), word(1), i),
+                    desktop_id: format!("app{}", i),
+                    exec_command: format!("/usr/bin/{}{} --flag", word(2), i),
+                    match_indices: Vec::new(),
+                    generic_name: format!("{} {}", word(3), word(4)),
+                    keywords: vec![word(5).to_string(), word(6).to_string()],
+                    categories: vec!["Utility".to_string(), "Office".to_string()],
+                    comment: format!("A {} for {}", word(7), word(8)),
+                    source: AppSource::Native,
+                    actions: if i % 10 == 0 {
+                        vec![DesktopAction { name: "New Window".to_string(), exec: "new-window".to_string() }]
+                    } else {
+                        Vec::new()
+                    },
+                    terminal: false,
+                    icon: String::new(),
+                    working_dir: None,
+                    desktop_file: None,
                 }
-            });
-            
-            ui.separator();
-            if ui.button("Back to Launcher").clicked() {
-                self.app_mode = AppMode::Launcher;
-            }
-        });
+            })
+            .collect()
     }
-}
-
-fn render_result_item(
-    ui: &mut egui::Ui,
-    result: &ResultType,
-    is_selected: bool,
-    theme: &Theme,
-    item_alpha: f32,
-    query: &str,
-) {
-    let text_rgb = theme.hex_to_rgb(&theme.text_color);
-    let sel_text_rgb = theme.hex_to_rgb(&theme.selection_text);
     
-    let color = if is_selected { sel_text_rgb } else { text_rgb };
-    let color_val = egui::Color32::from_rgba_premultiplied(
-        (color[0] * 255.0 * item_alpha) as u8,
-        (color[1] * 255.0 * item_alpha) as u8,
-        (color[2] * 255.0 * item_alpha) as u8,
-        (item_alpha * 255.0) as u8,
-    );
+    fn ranked(search: &CachedSearch) -> Vec<(i64, String)> {
+        let mut ranked: Vec<(i64, String)> = search.results.iter()
+            .map(|(score, result)| (*score, result.learning_key().unwrap_or_default()))
+            .collect();
+        ranked.sort_by(|a, b| b.0.cmp(&a.0).then_with(|| a.1.cmp(&b.1)));
+        ranked
+    }
     
-    match result {
-        ResultType::App(app) => {
-            render_highlighted_text(ui, &app.name, &app.match_indices, is_selected, theme, item_alpha);
-        }
-        ResultType::Calculator(res) => {
-            ui.label(
-                egui::RichText::new(format!("🧮 {} = {}", query, res))
-                    .color(color_val)
-                    .size(theme.font_size)
-            );
-        }
-        ResultType::Command(cmd) => {
-            ui.label(
-                egui::RichText::new(format!("💻 {}", cmd))
-                    .color(color_val)
-                    .size(theme.font_size)
-            );
-        }
-        ResultType::WebSearch(search_query) => {
-            ui.label(
-                egui::RichText::new(format!("🔍 Search DuckDuckGo: {}", search_query))
-                    .color(color_val)
-                    .size(theme.font_size)
-            );
-        }
-        ResultType::Url(url) => {
-            ui.label(
-                egui::RichText::new(format!("🌐 Open: {}", url))
-                    .color(color_val)
-                    .size(theme.font_size)
-            );
+    #[test]
+    fn match_apps_keeps_the_top_results_of_a_large_index() {
+        let apps = synthetic_apps(20_000);
+        let config = SearchConfig::default();
+        let index = build_app_index(&apps, config.transliterate);
+        let matcher = config.backend.matcher();
+        let max_results = config.provider("apps").max_results;
+        let no_learning = std::collections::HashSet::new();
+        let search = |query: &str, config: &SearchConfig, learned: &std::collections::HashSet<&str>, candidates: Option<&[usize]>| {
+            match_apps(&apps, &index, &AppQuery::new(query, config.transliterate), matcher.as_ref(), config, learned, candidates)
+        };
+        
+        let fire = search("fire", &config, &no_learning, None);
+        assert_eq!(fire.results.len(), max_results);
+        
+        let mut unlimited = config.clone();
+        for (_, settings) in &mut unlimited.providers {
+            settings.max_results = apps.len();
         }
-        ResultType::File(path) => {
-            let file_name = path.file_name().and_then(|n| n.to_str()).unwrap_or("Unknown");
-            let parent_dir = path.parent()
-                .and_then(|p| p.file_name())
-                .and_then(|n| n.to_str())
-                .unwrap_or("");
-            ui.label(
-                egui::RichText::new(format!("📄 {} ({})", file_name, parent_dir))
-                    .color(color_val)
-                    .size(theme.font_size)
-            );
+        let every_match = search("fire", &unlimited, &no_learning, None);
+        assert!(every_match.results.len() > max_results);
+        let best_scores: Vec<i64> = ranked(&every_match).into_iter().map(|(score, _)| score).take(max_results).collect();
+        let top_scores: Vec<i64> = ranked(&fire).into_iter().map(|(score, _)| score).collect();
+        assert_eq!(top_scores, best_scores);
+        
+        let weakest = ranked(&every_match).pop().unwrap().1;
+        let weakest_id = weakest.trim_start_matches("app:").trim_start_matches("action:").split(':').next().unwrap();
+        let learned = std::collections::HashSet::from([weakest_id]);
+        let with_learned = search("fire", &config, &learned, None);
+        assert_eq!(with_learned.results.len(), max_results + 1);
+        assert!(ranked(&with_learned).iter().any(|(_, key)| *key == weakest));
+        
+        let fir = search("fir", &config, &no_learning, None);
+        let narrowed = search("fire", &config, &no_learning, fir.candidates.as_deref());
+        assert_eq!(ranked(&narrowed), ranked(&fire));
+        assert_eq!(narrowed.candidates, fire.candidates);
+        
+        let nothing = search("xyzq", &config, &no_learning, None);
+        assert!(nothing.results.is_empty());
+        assert_eq!(nothing.candidates.as_deref(), Some(&[][..]));
+    }
+    
+    #[test]
+    fn match_apps_stays_within_budget_on_a_20k_index() {
+        let apps = synthetic_apps(20_000);
+        let config = SearchConfig::default();
+        let index = build_app_index(&apps, config.transliterate);
+        let matcher = config.backend.matcher();
+        let learned = std::collections::HashSet::new();
+        let scale = if cfg!(debug_assertions) { 15 } else { 1 };
+        let time = |query: &str, candidates: Option<&[usize]>| {
+            let query = AppQuery::new(query, config.transliterate);
+            (0..5)
+                .map(|_| {
+                    let started = Instant::now();
+                    match_apps(&apps, &index, &query, matcher.as_ref(), &config, &learned, candidates);
+                    started.elapsed()
+                })
+                .min()
+                .unwrap()
+        };
+        let within = |query: &str, candidates: Option<&[usize]>, budget: Duration| {
+            let elapsed = time(query, candidates);
+            assert!(elapsed < budget * scale, "{:?} took {:?}, budget {:?}", query, elapsed, budget * scale);
+        };
+        
+        for query in ["xyzq", "zzzz1"] {
+            within(query, None, Duration::from_millis(1));
         }
-        ResultType::Emoji(name, emoji) => {
-            ui.label(
-                egui::RichText::new(format!("{} :{}", emoji, name))
-                    .color(color_val)
-                    .size(theme.font_size)
-            );
+        for query in ["vsc", "firefox", "text editor"] {
+            within(query, None, Duration::from_millis(5));
         }
-        ResultType::Currency(from, to, result) => {
-            ui.label(
-                egui::RichText::new(format!("💱 {} {} = {:.2} {} (Live)", query, from, result, to))
-                    .color(color_val)
-                    .size(theme.font_size)
-            );
+        for query in ["f", "fi", "fir", "fire", "tex", "ff", "café"] {
+            within(query, None, Duration::from_millis(50));
         }
+        
+        let firef = match_apps(&apps, &index, &AppQuery::new("firef", config.transliterate), matcher.as_ref(), &config, &learned, None);
+        assert!(firef.candidates.as_ref().is_some_and(|candidates| !candidates.is_empty() && candidates.len() < apps.len() / 10));
+        within("firefo", firef.candidates.as_deref(), Duration::from_millis(5));
     }
-}
-
-fn execute_result(result: &ResultType) {
-    match result {
-        ResultType::App(app) => launch_app(&app.exec_command),
-        ResultType::Calculator(res) => copy_to_clipboard(res),
-        ResultType::Command(cmd) => execute_command(cmd),
-        ResultType::WebSearch(query) => open_web_search(query),
-        ResultType::Url(url) => open_url(url),
-        ResultType::File(path) => open_file(path),
-        ResultType::Emoji(_, emoji) => copy_to_clipboard(emoji),
-        ResultType::Currency(_, _, result) => copy_to_clipboard(&result.to_string()),
+    
+    #[test]
+    fn match_apps_keeps_apps_that_a_longer_query_can_match() {
+        let mut notes = test_app("Zed");
+        notes.comment = "Scratchpad".to_string();
+        let apps = vec![notes, test_app("Firefox")];
+        let config = SearchConfig { comment_weight: 1, ..SearchConfig::default() };
+        let index = build_app_index(&apps, config.transliterate);
+        let matcher = config.backend.matcher();
+        let learned = std::collections::HashSet::new();
+        let search = |query: &str, candidates: Option<&[usize]>| {
+            match_apps(&apps, &index, &AppQuery::new(query, config.transliterate), matcher.as_ref(), &config, &learned, candidates)
+        };
+        
+        let short = search("s", None);
+        assert!(short.results.is_empty());
+        assert_eq!(short.candidates.as_deref(), Some(&[0][..]));
+        
+        let full = search("scratchpad", None);
+        assert_eq!(ranked(&full).len(), 1);
+        assert_eq!(ranked(&search("scratchpad", short.candidates.as_deref())), ranked(&full));
     }
-}
-
-fn render_highlighted_text(
-    ui: &mut egui::Ui,
-    text: &str,
-    match_indices: &[usize],
-    is_selected: bool,
-    theme: &Theme,
-    alpha: f32,
-) {
-    let normal_color = if is_selected {
-        theme.hex_to_rgb(&theme.selection_text)
-    } else {
-        theme.hex_to_rgb(&theme.text_color)
-    };
     
-    let highlight_color = theme.hex_to_rgb(&theme.highlight_color);
+    fn cached(candidates: &[usize], name: &str) -> CachedSearch {
+        CachedSearch {
+            candidates: Some(candidates.to_vec()),
+            results: vec![(1, ResultType::App(test_app(name)))],
+        }
+    }
     
-    ui.horizontal(|ui| {
-        ui.spacing_mut().item_spacing.x = 0.0;
+    #[test]
+    fn search_cache_narrows_from_the_longest_cached_prefix() {
+        let mut cache = SearchCache::default();
+        cache.sync(1);
+        cache.insert("apps", "f", cached(&[0, 1, 2, 3], "Files"));
+        cache.insert("apps", "fir", cached(&[1, 2], "Firefox"));
+        cache.insert("apps", "firm", cached(&[], "Firmware"));
+        cache.insert("quicklinks", "fire", cached(&[5], "Fire"));
         
-        for (i, ch) in text.chars().enumerate() {
-            let base_color = if match_indices.contains(&i) {
-                highlight_color
-            } else {
-                normal_color
-            };
-            
-            let color = egui::Color32::from_rgba_premultiplied(
-                (base_color[0] * 255.0 * alpha) as u8,
-                (base_color[1] * 255.0 * alpha) as u8,
-                (base_color[2] * 255.0 * alpha) as u8,
-                (alpha * 255.0) as u8,
-            );
-            
-            ui.label(
-                egui::RichText::new(ch.to_string())
-                    .color(color)
-                    .font(egui::FontId::proportional(theme.font_size))
-            );
-        }
-    });
-}
-
-#[derive(Debug, Deserialize)]
-struct ExchangeRatesResponse {
-    rates: std::collections::HashMap<String, f64>,
-}
-
-fn is_calculation(query: &str) -> bool {
-    let trimmed = query.trim();
+        assert_eq!(cache.narrowest("apps", "firef"), Some(&[1, 2][..]));
+        assert_eq!(cache.narrowest("apps", "fi"), Some(&[0, 1, 2, 3][..]));
+        assert_eq!(cache.narrowest("apps", "code"), None);
+        assert_eq!(cache.narrowest("files", "fire"), None);
+    }
     
-    let has_operator = trimmed.contains('+') || 
-                      trimmed.contains('-') || 
-                      trimmed.contains('*') || 
-                      trimmed.contains('/') ||
-                      trimmed.contains('%') ||
-                      trimmed.contains('^');
+    #[test]
+    fn search_cache_reuses_results_on_backspace() {
+        let mut cache = SearchCache::default();
+        cache.sync(1);
+        cache.insert("apps", "fir", cached(&[1, 2], "Firefox"));
+        cache.insert("apps", "fire", cached(&[1], "Firefox"));
+        
+        cache.sync(1);
+        let fir = cache.get("apps", "fir").expect("cached query");
+        assert_eq!(fir.candidates.as_deref(), Some(&[1, 2][..]));
+        assert_eq!(fir.results.len(), 1);
+        assert!(cache.get("quicklinks", "fir").is_none());
+    }
     
-    let has_numbers = trimmed.chars().any(|c| c.is_ascii_digit());
+    #[test]
+    fn search_cache_clears_when_the_index_changes() {
+        let mut cache = SearchCache::default();
+        cache.sync(1);
+        cache.insert("apps", "fir", cached(&[1, 2], "Firefox"));
+        
+        cache.sync(2);
+        assert!(cache.get("apps", "fir").is_none());
+        assert_eq!(cache.narrowest("apps", "fire"), None);
+        
+        for i in 0..SEARCH_CACHE_MAX_ENTRIES {
+            cache.insert("apps", &i.to_string(), cached(&[], "App"));
+        }
+        cache.sync(2);
+        assert!(cache.entries.is_empty());
+    }
     
-    let has_letters = trimmed.chars().any(|c| c.is_ascii_alphabetic() && c != 'e' && c != 'E' && c != 'p' && c != 'P' && c != 'i' && c != 'I');
+    #[test]
+    fn learned_file_and_emoji_results_rank_first() {
+        let learned = |query: &str, key: &str| LearnedSelection {
+            query: query.to_string(),
+            key: key.to_string(),
+            count: 3.0,
+            last_used: unix_now(),
+        };
+        let memory = SelectionMemory {
+            entries: vec![learned("e:hea", "emoji:sparkling_heart"), learned("file:report", "file:/docs/report-final.pdf")],
+        };
+        
+        let mut emojis = vec![
+            ResultType::Emoji("heart".to_string(), "❤️".to_string()),
+            ResultType::Emoji("heartbeat".to_string(), "💓".to_string()),
+            ResultType::Emoji("sparkling_heart".to_string(), "💖".to_string()),
+        ];
+        memory.rank_learned("e:hea", &mut emojis);
+        let order: Vec<String> = emojis.iter().filter_map(|result| result.learning_key()).collect();
+        assert_eq!(order, ["emoji:sparkling_heart", "emoji:heart", "emoji:heartbeat"]);
+        
+        let mut files = vec![
+            ResultType::File(PathBuf::from("/docs/report.txt")),
+            ResultType::File(PathBuf::from("/docs/report-final.pdf")),
+        ];
+        memory.rank_learned("file:rep", &mut files);
+        assert_eq!(files[0].learning_key().as_deref(), Some("file:/docs/report-final.pdf"));
+        
+        memory.rank_learned("e:smile", &mut emojis);
+        assert_eq!(emojis[0].learning_key().as_deref(), Some("emoji:sparkling_heart"));
+    }
     
-    let reasonable_length = trimmed.len() >= 2 && trimmed.len() <= 50;
+    #[test]
+    fn plugin_folder_name_is_never_empty() {
+        let archive = std::path::Path::new("/downloads/jp-tools.zip");
+        assert_eq!(plugin_folder_name("GitHub Repos", archive), "github-repos");
+        assert_eq!(plugin_folder_name("日本語", archive), "jp-tools");
+        assert_eq!(plugin_folder_name("!!!", archive), "jp-tools");
+        
+        let fallback = plugin_folder_name("!!!", std::path::Path::new("/downloads/___.zip"));
+        assert!(fallback.starts_with("plugin-") && fallback.len() > "plugin-".len());
+        assert_ne!(get_plugins_dir().join(&fallback), get_plugins_dir());
+    }
     
-    has_operator && has_numbers && !has_letters && reasonable_length
-}
-
-fn normalize_currency_code(code: &str) -> Option<String> {
-    let code_lower = code.to_lowercase();
-    let result = match code_lower.as_str() {
-        "usd" | "dollar" | "dollars" => "USD",
-        "eur" | "euro" | "euros" => "EUR", 
-        "gbp" | "pound" | "pounds" | "sterling" => "GBP",
-        "jpy" | "yen" => "JPY",
-        "cad" | "canadian dollar" => "CAD",
-        "aud" | "australian dollar" => "AUD",
-        "chf" | "swiss franc" => "CHF",
-        "cny" | "yuan" | "renminbi" => "CNY",
-        "inr" | "rupee" | "rupees" => "INR",
-        _ if code.len() == 3 => {
-            return Some(code.to_uppercase());
+    fn manifest_with_flint_version(flint_version: &str) -> PluginManifest {
+        serde_json::from_value(serde_json::json!({
+            "name": "Test",
+            "trigger": "t",
+            "executable": "run.sh",
+            "flint_version": flint_version,
+        }))
+        .unwrap()
+    }
+    
+    #[test]
+    fn parse_version_reads_numeric_components() {
+        assert_eq!(parse_version("1.2.3"), vec![1, 2, 3]);
+        assert_eq!(parse_version(" >=v1.4 "), vec![1, 4]);
+        assert_eq!(parse_version("2.x"), vec![2, 0]);
+        assert!(parse_version("1.0") < parse_version("1.0.1"));
+        assert!(parse_version("1.10") > parse_version("1.9"));
+    }
+    
+    #[test]
+    fn check_plugin_compatibility_requires_the_same_major_version() {
+        let current = env!("CARGO_PKG_VERSION");
+        let major = parse_version(current)[0];
+        for compatible in ["", current, &format!(">={}", major), &format!("v{}.0", major)] {
+            assert!(check_plugin_compatibility(&manifest_with_flint_version(compatible)).is_ok(), "{:?}", compatible);
         }
-        _ => return None,
-    };
-    Some(result.to_string())
-}
-
-async fn convert_currency_online(query: &str) -> Option<(String, String, f64)> {
-    let parts: Vec<&str> = query.split_whitespace().collect();
+        for incompatible in [format!("{}.0", major + 1), format!("{}.999", major), format!("{}.0", major.saturating_sub(1))] {
+            let error = check_plugin_compatibility(&manifest_with_flint_version(&incompatible)).unwrap_err();
+            assert!(error.contains(&incompatible), "{}", error);
+        }
+    }
+    
+    fn test_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("flint-{}-{}", name, uuid::Uuid::new_v4()));
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+    
+    fn plugin_archive(path: &std::path::Path, name: &str, files: &[(&str, &str)]) {
+        let mut writer = zip::ZipWriter::new(File::create(path).unwrap());
+        let options = zip::write::FileOptions::default().compression_method(zip::CompressionMethod::Stored);
+        let manifest = serde_json::json!({ "name": name, "trigger": "t", "executable": "run.sh" });
+        writer.start_file(format!("{}/plugin.json", name), options).unwrap();
+        writer.write_all(manifest.to_string().as_bytes()).unwrap();
+        for (file, content) in files {
+            writer.start_file(format!("{}/{}", name, file), options).unwrap();
+            writer.write_all(content.as_bytes()).unwrap();
+        }
+        writer.finish().unwrap();
+    }
+    
+    #[test]
+    fn install_plugin_archive_replaces_the_previous_version() {
+        let dir = test_dir("install");
+        let plugins_dir = dir.join("plugins");
+        let archive = dir.join("notes.zip");
+        
+        plugin_archive(&archive, "Notes", &[("run.sh", "v1"), ("old.txt", "stale")]);
+        assert_eq!(install_plugin_archive_into(&archive, &plugins_dir), Ok("Notes".to_string()));
+        fs::write(plugins_dir.join("notes/settings.json"), "{\"token\":\"abc\"}").unwrap();
+        
+        plugin_archive(&archive, "Notes", &[("run.sh", "v2")]);
+        assert_eq!(install_plugin_archive_into(&archive, &plugins_dir), Ok("Notes".to_string()));
+        let installed = plugins_dir.join("notes");
+        assert_eq!(fs::read_to_string(installed.join("run.sh")).unwrap(), "v2");
+        assert!(!installed.join("old.txt").exists());
+        assert_eq!(fs::read_to_string(installed.join("settings.json")).unwrap(), "{\"token\":\"abc\"}");
+        assert_eq!(fs::read_dir(&plugins_dir).unwrap().count(), 1);
+        
+        let _ = fs::remove_dir_all(dir);
+    }
     
-    if parts.len() >= 3 {
-        let mut amount_str = parts[0];
-        let mut from_currency_str = parts[1];
-        let mut to_currency_str = parts.get(2).copied().unwrap_or("");
+    #[test]
+    fn install_plugin_archive_keeps_the_installed_version_when_extraction_fails() {
+        let dir = test_dir("install-corrupt");
+        let plugins_dir = dir.join("plugins");
+        let archive = dir.join("notes.zip");
+        plugin_archive(&archive, "Notes", &[("run.sh", "v1")]);
+        install_plugin_archive_into(&archive, &plugins_dir).unwrap();
+        
+        plugin_archive(&archive, "Notes", &[("run.sh", "version-two-payload")]);
+        let mut bytes = fs::read(&archive).unwrap();
+        let payload = bytes.windows(19).position(|window| window == b"version-two-payload").unwrap();
+        bytes[payload] = b'V';
+        fs::write(&archive, bytes).unwrap();
+        
+        let error = install_plugin_archive_into(&archive, &plugins_dir).unwrap_err();
+        assert!(error.starts_with("Failed to extract"), "{}", error);
+        assert_eq!(fs::read_to_string(plugins_dir.join("notes/run.sh")).unwrap(), "v1");
+        assert_eq!(fs::read_dir(&plugins_dir).unwrap().count(), 1);
         
-        if parts[0].to_lowercase() == "convert" && parts.len() >= 4 {
-            amount_str = parts[1];
-            from_currency_str = parts[2];
-            to_currency_str = parts.get(3).copied().unwrap_or("");
+        let _ = fs::remove_dir_all(dir);
+    }
+    
+    #[test]
+    fn script_lua_cannot_load_code_or_files() {
+        let lua = new_script_lua().unwrap();
+        for name in LUA_REMOVED_GLOBALS.into_iter().chain(["io", "os", "package", "require", "debug"]) {
+            assert!(lua.globals().get::<_, mlua::Value>(name).unwrap().is_nil(), "{} is available", name);
         }
+        let joined: String = lua.load("return table.concat({ string.upper('a'), math.floor(2.5), utf8.char(233) }, ',')").eval().unwrap();
+        assert_eq!(joined, "A,2,é");
+    }
+    
+    #[cfg(not(target_os = "windows"))]
+    #[test]
+    fn capture_command_output_kills_the_whole_group_on_timeout() {
+        let dir = test_dir("capture");
+        let pid_file = dir.join("pid");
+        let cmd = format!("sleep 30 & echo $! > '{}'; wait", pid_file.display());
         
-        if parts.len() >= 4 && parts[2].to_lowercase() == "to" {
-            to_currency_str = parts[3];
-        } else if parts.len() >= 4 && parts[0].to_lowercase() == "convert" && parts[3].to_lowercase() == "to" {
-            to_currency_str = parts.get(4).copied().unwrap_or("");
+        let runtime = tokio::runtime::Runtime::new().unwrap();
+        let started = Instant::now();
+        let lines = runtime.block_on(capture_command_output(&cmd, Duration::from_millis(500)));
+        assert!(started.elapsed() < Duration::from_secs(10));
+        assert_eq!(lines.first().map(|line| line.text.as_str()), Some("⏱ Timed out after 0s"));
+        
+        let sleep_pid = fs::read_to_string(&pid_file).unwrap().trim().to_string();
+        let running = || {
+            fs::read_to_string(format!("/proc/{}/stat", sleep_pid))
+                .is_ok_and(|stat| !stat.rsplit(')').next().unwrap_or_default().trim_start().starts_with('Z'))
+        };
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while running() && Instant::now() < deadline {
+            thread::sleep(Duration::from_millis(20));
         }
+        assert!(!running(), "sleep {} outlived the timeout", sleep_pid);
         
-        if to_currency_str.is_empty() {
-            return None;
+        let _ = fs::remove_dir_all(dir);
+    }
+    
+    fn terminal_config(terminal: &str, terminal_args: &str) -> CommandConfig {
+        CommandConfig {
+            terminal: terminal.to_string(),
+            terminal_args: terminal_args.to_string(),
+            ..CommandConfig::default()
         }
+    }
+    
+    fn wrap(config: &CommandConfig, command: &[&str]) -> Vec<String> {
+        config.terminal_command(command.iter().map(|arg| arg.to_string()).collect())
+    }
+    
+    #[test]
+    fn terminal_command_substitutes_the_args_template() {
+        assert_eq!(wrap(&terminal_config("term", "--title Flint -e {} --hold"), &["htop", "-d", "5"]), vec!["term", "--title", "Flint", "-e", "htop", "-d", "5", "--hold"]);
+        assert_eq!(wrap(&terminal_config("term", "--hold"), &["ranger", "/tmp/a b"]), vec!["term", "--hold", "ranger", "/tmp/a b"]);
+        assert_eq!(wrap(&terminal_config("term", "{}"), &["sh", "-c", "echo {} done"]), vec!["term", "sh", "-c", "echo {} done"]);
+        assert_eq!(wrap(&terminal_config("term", "  "), &["htop"]), wrap(&terminal_config("term", DEFAULT_TERMINAL_ARGS), &["htop"]));
+    }
+    
+    #[cfg(not(target_os = "windows"))]
+    #[test]
+    fn terminal_args_template_defaults_per_emulator() {
+        assert_eq!(terminal_args_template("gnome-terminal"), "-- {}");
+        assert_eq!(terminal_args_template("/usr/bin/kitty"), "{}");
+        assert_eq!(terminal_args_template("wezterm"), "start -- {}");
+        assert_eq!(terminal_args_template("xfce4-terminal"), "-x {}");
+        assert_eq!(terminal_args_template("unknown-term"), "-e {}");
         
-        if let (Ok(amount), Some(from_currency), Some(to_currency)) = (
-            amount_str.parse::<f64>(),
-            normalize_currency_code(from_currency_str),
-            normalize_currency_code(to_currency_str),
-        ) {
-            if from_currency == to_currency {
-                return Some((from_currency.to_string(), to_currency.to_string(), amount));
-            }
-            
-            let client = reqwest::Client::new();
-            let url = format!("https://api.exchangerate-api.com/v4/latest/{}", from_currency);
-            
-            match client.get(&url).send().await {
-                Ok(response) => {
-                    if response.status().is_success() {
-                        if let Ok(exchange_data) = response.json::<ExchangeRatesResponse>().await {
-                            if let Some(rate) = exchange_data.rates.get(&to_currency) {
-                                let converted = amount * rate;
-                                return Some((from_currency.to_string(), to_currency.to_string(), converted));
-                            }
-                        }
-                    }
-                }
-                Err(_) => {
-                    let fallback_url = format!("https://api.frankfurter.app/latest?from={}", from_currency);
-                    if let Ok(fallback_response) = client.get(&fallback_url).send().await {
-                        if fallback_response.status().is_success() {
-                            if let Ok(exchange_data) = fallback_response.json::<ExchangeRatesResponse>().await {
-                                if let Some(rate) = exchange_data.rates.get(&to_currency) {
-                                    let converted = amount * rate;
-                                    return Some((from_currency.to_string(), to_currency.to_string(), converted));
-                                }
-                            }
-                        }
-                    }
-                    return None;
-                }
+        assert_eq!(wrap(&terminal_config("gnome-terminal", ""), &["htop"]), vec!["gnome-terminal", "--", "htop"]);
+        assert_eq!(wrap(&terminal_config("/usr/bin/foot", ""), &["htop"]), vec!["/usr/bin/foot", "htop"]);
+        assert_eq!(wrap(&terminal_config("wezterm", ""), &["ranger"]), vec!["wezterm", "start", "--", "ranger"]);
+        assert_eq!(wrap(&terminal_config("alacritty", "--hold -e {}"), &["htop"]), vec!["alacritty", "--hold", "-e", "htop"]);
+    }
+    
+    #[cfg(target_os = "windows")]
+    #[test]
+    fn terminal_args_template_defaults_per_emulator() {
+        assert_eq!(terminal_args_template("wt"), "cmd /K {}");
+        assert_eq!(terminal_args_template("C:\\Windows\\System32\\cmd.exe"), "/K {}");
+        assert_eq!(wrap(&terminal_config("wt", ""), &["htop"]), vec!["wt", "cmd", "/K", "htop"]);
+    }
+    
+    #[test]
+    fn expand_snippet_replaces_placeholders() {
+        let inputs = vec![("Name".to_string(), "Ada".to_string())];
+        let expanded = expand_snippet("{date} {time}\nThanks,\n{input:Name}", &inputs, &fixed_snippet_config());
+        assert_eq!(expanded, "DATE TIME\nThanks,\nAda");
+    }
+    
+    #[test]
+    fn expand_snippet_expands_placeholders_inside_braces() {
+        let config = fixed_snippet_config();
+        assert_eq!(
+            expand_snippet("function() { return {date}; }", &[], &config),
+            "function() { return DATE; }"
+        );
+        assert_eq!(
+            expand_snippet("{\"stamp\": \"{date} {time}\", \"nested\": {\"a\": 1}}", &[], &config),
+            "{\"stamp\": \"DATE TIME\", \"nested\": {\"a\": 1}}"
+        );
+        assert_eq!(expand_snippet("a {{date}} b", &[], &config), "a {DATE} b");
+    }
+    
+    #[test]
+    fn expand_snippet_keeps_literal_braces() {
+        let config = fixed_snippet_config();
+        assert_eq!(expand_snippet("body { color: red; }", &[], &config), "body { color: red; }");
+        assert_eq!(expand_snippet("} {unknown} {input:Missing} {", &[], &config), "} {unknown} {input:Missing} {");
+        assert_eq!(expand_snippet("{date", &[], &config), "{date");
+    }
+    
+    #[test]
+    fn parse_shell_link_reads_unicode_link_info() {
+        let link = parse_shell_link(include_bytes!("../tests/fixtures/local_unicode.lnk")).unwrap();
+        assert_eq!(link.target, "C:\\Program Files\\Mozilla Firefox\\firefox.exe");
+        assert_eq!(link.description, "Browse the Web — Firefox");
+        assert_eq!(link.working_dir, "C:\\Program Files\\Mozilla Firefox");
+        assert_eq!(link.arguments, "-P \"default\" %TEMP%");
+        assert_eq!(link.icon_location, "%ProgramFiles%\\Mozilla Firefox\\firefox.exe");
+        assert_eq!(link.icon_index, 2);
+    }
+    
+    #[test]
+    fn parse_shell_link_reads_ansi_strings_and_environment_target() {
+        let link = parse_shell_link(include_bytes!("../tests/fixtures/ansi_environment.lnk")).unwrap();
+        assert_eq!(link.target, "%windir%\\system32\\cmd.exe");
+        assert_eq!(link.arguments, "/k echo hi");
+    }
+    
+    #[test]
+    fn parse_shell_link_reads_relative_path_and_arguments() {
+        let link = parse_shell_link(include_bytes!("../tests/fixtures/relative_arguments.lnk")).unwrap();
+        assert_eq!(link.target, "D:\\Tools\\Café\\tool.exe");
+        assert_eq!(link.relative_path, "..\\..\\Tools\\Café\\tool.exe");
+        assert_eq!(link.working_dir, "D:\\Tools\\Café");
+        assert_eq!(link.arguments, "--profile \"work\" --safe");
+        assert!(link.description.is_empty());
+    }
+    
+    #[test]
+    fn parse_shell_link_reads_network_share() {
+        let link = parse_shell_link(include_bytes!("../tests/fixtures/network_share.lnk")).unwrap();
+        assert_eq!(link.target, "\\\\server\\share\\tools\\app.exe");
+    }
+    
+    #[test]
+    fn parse_shell_link_rejects_truncated_and_corrupt_input() {
+        assert!(parse_shell_link(include_bytes!("../tests/fixtures/truncated.lnk")).is_err());
+        assert!(parse_shell_link(include_bytes!("../tests/fixtures/not_a_link.lnk")).is_err());
+        assert!(parse_shell_link(&[]).is_err());
+        
+        let fixtures: [&[u8]; 4] = [
+            include_bytes!("../tests/fixtures/local_unicode.lnk"),
+            include_bytes!("../tests/fixtures/ansi_environment.lnk"),
+            include_bytes!("../tests/fixtures/relative_arguments.lnk"),
+            include_bytes!("../tests/fixtures/network_share.lnk"),
+        ];
+        for data in fixtures {
+            for len in 0..data.len() {
+                let _ = parse_shell_link(&data[..len]);
             }
+            let mut corrupt = data.to_vec();
+            corrupt.iter_mut().skip(0x4C).for_each(|byte| *byte = 0xFF);
+            let _ = parse_shell_link(&corrupt);
         }
     }
-    None
-}
-
-#[cfg(target_os = "windows")]
-fn copy_to_clipboard(text: &str) {
-    let _ = Command::new("cmd")
-        .args(&["/C", &format!("echo {} | clip", text)])
-        .spawn();
-}
-
-#[cfg(not(target_os = "windows"))]
-fn copy_to_clipboard(text: &str) {
-    let _ = Command::new("xclip")
-        .arg("-selection")
-        .arg("clipboard")
-        .arg("-i")
-        .stdin(std::process::Stdio::piped())
-        .spawn()
-        .and_then(|mut child| {
-            child.stdin.as_mut().map(|stdin| {
-                let _ = stdin.write_all(text.as_bytes());
-            });
-            Ok(child)
-        });
-}
-
-#[cfg(target_os = "windows")]
-fn execute_command(cmd: &str) {
-    let _ = Command::new("cmd")
-        .args(&["/C", "start", "cmd", "/C", cmd])
-        .spawn();
-}
-
-#[cfg(not(target_os = "windows"))]
-fn execute_command(cmd: &str) {
-    let _ = Command::new("sh")
-        .arg("-c")
-        .arg(cmd)
-        .spawn();
-}
-
-#[cfg(target_os = "windows")]
-fn open_web_search(query: &str) {
-    let url = format!("https://duckduckgo.com/?q={}", urlencoding::encode(query));
-    open_url(&url);
-}
-
-#[cfg(not(target_os = "windows"))]
-fn open_web_search(query: &str) {
-    let url = format!("https://duckduckgo.com/?q={}", urlencoding::encode(query));
-    let _ = Command::new("xdg-open")
-        .arg(&url)
-        .spawn();
-}
-
-#[cfg(target_os = "windows")]
-fn open_url(url: &str) {
-    let _ = Command::new("cmd")
-        .args(&["/C", "start", "", url])
-        .spawn();
-}
-
-#[cfg(not(target_os = "windows"))]
-fn open_url(url: &str) {
-    let _ = Command::new("xdg-open")
-        .arg(url)
-        .spawn();
-}
-
-#[cfg(target_os = "windows")]
-fn open_file(path: &PathBuf) {
-    let _ = Command::new("cmd")
-        .args(&["/C", "start", "", &path.to_string_lossy()])
-        .spawn();
-}
-
-#[cfg(not(target_os = "windows"))]
-fn open_file(path: &PathBuf) {
-    let _ = Command::new("xdg-open")
-        .arg(path)
-        .spawn();
-}
-
-fn search_files(query: &str) -> Vec<PathBuf> {
-    let mut results = Vec::new();
-    let query_lower = query.to_lowercase();
     
-    let search_dirs = [
-        dirs::download_dir(),
-        dirs::document_dir(),
-        dirs::desktop_dir(),
-        dirs::picture_dir(),
-        dirs::audio_dir(),
-        dirs::video_dir(),
-    ];
+    #[test]
+    fn parse_internet_shortcut_reads_url_and_icon() {
+        let content = "\u{feff}[DEFAULT]\r\nBASEURL=https://ignored.example\r\n[internetshortcut]\r\nurl = https://example.com/?a=1&b=2\r\nICONFILE=C:\\Icons\\site.ico\r\nIconIndex=0\r\n";
+        assert_eq!(
+            parse_internet_shortcut(content),
+            Some(InternetShortcut {
+                url: "https://example.com/?a=1&b=2".to_string(),
+                icon_file: "C:\\Icons\\site.ico".to_string(),
+            })
+        );
+        
+        let content = "[ InternetShortcut ]\nURL=steam://rungameid/570\n";
+        assert_eq!(parse_internet_shortcut(content).map(|shortcut| shortcut.icon_file), Some(String::new()));
+    }
     
-    for dir_option in &search_dirs {
-        if let Some(dir) = dir_option {
-            if let Ok(entries) = fs::read_dir(dir) {
-                for entry in entries.flatten() {
-                    let path = entry.path();
-                    if let Some(file_name) = path.file_name().and_then(|n| n.to_str()) {
-                        if file_name.to_lowercase().contains(&query_lower) {
-                            results.push(path);
-                            if results.len() >= 5 {
-                                break;
-                            }
-                        }
-                    }
-                }
+    #[test]
+    fn parse_internet_shortcut_requires_a_url() {
+        assert_eq!(parse_internet_shortcut("[InternetShortcut]\nIconFile=site.ico\n"), None);
+        assert_eq!(parse_internet_shortcut("[InternetShortcut]\nURL=\n"), None);
+        assert_eq!(parse_internet_shortcut("[Other]\nURL=https://example.com\n"), None);
+        assert_eq!(parse_internet_shortcut(""), None);
+    }
+    
+    #[test]
+    fn internet_shortcuts_only_accept_known_schemes() {
+        assert!(is_internet_shortcut_url("https://x&calc"));
+        assert!(is_internet_shortcut_url("HTTP://example.com"));
+        assert!(is_internet_shortcut_url("mailto:someone@example.com"));
+        assert!(is_internet_shortcut_url("steam://rungameid/570"));
+        assert!(!is_internet_shortcut_url("file:///C:/Windows/System32/calc.exe"));
+        assert!(!is_internet_shortcut_url("javascript:alert(1)"));
+        assert!(!is_internet_shortcut_url("C:\\Windows\\System32\\calc.exe"));
+        assert!(!is_internet_shortcut_url("& calc"));
+        assert!(!is_internet_shortcut_url(""));
+    }
+    
+    fn fixture_path(name: &str) -> PathBuf {
+        PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests").join("fixtures").join(name)
+    }
+    
+    #[test]
+    fn read_version_info_reads_pe32_and_pe32_plus() {
+        assert_eq!(
+            read_version_info(&fixture_path("pe32.exe")),
+            Ok(VersionInfo {
+                product_name: "Notepad++ Editor".to_string(),
+                file_description: "Notepad++".to_string(),
+                company_name: "Don Ho".to_string(),
+            })
+        );
+        assert_eq!(
+            read_version_info(&fixture_path("pe32_plus.exe")),
+            Ok(VersionInfo {
+                product_name: "Firefox".to_string(),
+                file_description: "Firefox".to_string(),
+                company_name: "Mozilla Corporation".to_string(),
+            })
+        );
+        assert_eq!(
+            read_version_info(&fixture_path("product_name_only.exe")),
+            Ok(VersionInfo { product_name: "Krita".to_string(), ..VersionInfo::default() })
+        );
+    }
+    
+    #[test]
+    fn read_version_info_rejects_unusable_executables() {
+        assert_eq!(read_version_info(&fixture_path("no_resources.exe")), Err("Executable has no resources".to_string()));
+        assert_eq!(read_version_info(&fixture_path("truncated_header.exe")), Err("Executable headers are truncated".to_string()));
+        assert_eq!(read_version_info(&fixture_path("not_pe.exe")), Err("Not a PE executable".to_string()));
+        assert_eq!(read_version_info(&fixture_path("local_unicode.lnk")), Err("Not an executable".to_string()));
+    }
+    
+    #[test]
+    fn version_resource_parsing_survives_truncated_input() {
+        let data = include_bytes!("../tests/fixtures/pe32_plus.exe");
+        for len in 0..data.len() {
+            let headers = &data[..len];
+            if let Ok((offset, size, section_rva, resource_rva)) = pe_resource_section(headers) {
+                let start = (offset as usize).min(len);
+                let end = (start + size).min(len);
+                let _ = parse_version_resource(&headers[start..end], section_rva, resource_rva);
             }
         }
+        
+        let (offset, size, section_rva, resource_rva) = pe_resource_section(data).unwrap();
+        let section = &data[offset as usize..offset as usize + size];
+        for len in 0..section.len() {
+            let _ = parse_version_resource(&section[..len], section_rva, resource_rva);
+            let _ = version_blocks(&section[len..]);
+        }
     }
     
-    results.sort_by(|a, b| {
-        let a_name = a.file_name().and_then(|n| n.to_str()).unwrap_or("");
-        let b_name = b.file_name().and_then(|n| n.to_str()).unwrap_or("");
-        a_name.len().cmp(&b_name.len())
-    });
+    #[test]
+    fn wildcard_match_handles_stars_and_question_marks() {
+        assert!(wildcard_match("unins*", "unins000"));
+        assert!(wildcard_match("unins*", "unins"));
+        assert!(!wildcard_match("unins*", "run uninstaller"));
+        assert!(wildcard_match("*crash*report*", "mozilla crash reporter"));
+        assert!(wildcard_match("*crash*report*", "crashreport"));
+        assert!(!wildcard_match("*crash*report*", "report crash"));
+        assert!(wildcard_match("setup?", "setup2"));
+        assert!(!wildcard_match("setup?", "setup"));
+        assert!(!wildcard_match("setup?", "setup12"));
+        assert!(wildcard_match("?", "é"));
+        assert!(wildcard_match("*", ""));
+        assert!(!wildcard_match("", "setup"));
+        assert!(wildcard_match("update", "update"));
+        assert!(!wildcard_match("update", "updater"));
+    }
     
-    results.into_iter().take(8).collect()
-}
-
-fn search_emojis(query: &str) -> Vec<(String, String)> {
-    let query_lower = query.to_lowercase();
+    #[test]
+    fn is_filtered_app_checks_every_name_case_insensitively() {
+        let filters = vec!["unins*".to_string(), "*helper".to_string()];
+        assert!(is_filtered_app(&filters, &["Firefox", "", "Unins000"]));
+        assert!(is_filtered_app(&filters, &["Chrome Helper"]));
+        assert!(!is_filtered_app(&filters, &["Firefox", "firefox.exe", "Firefox"]));
+        assert!(!is_filtered_app(&filters, &[""]));
+        assert!(!is_filtered_app(&[], &["Unins000"]));
+    }
     
-    let common_aliases: Vec<(&str, &str)> = vec![
-        ("smile", "😊"), ("happy", "😊"), ("laugh", "😂"), ("heart", "❤️"), ("love", "❤️"),
-        ("kiss", "😘"), ("cool", "😎"), ("thinking", "🤔"), ("thumbsup", "👍"), ("like", "👍"),
-        ("ok", "👌"), ("clap", "👏"), ("pray", "🙏"), ("wave", "👋"), ("muscle", "💪"),
-        ("eyes", "👀"), ("cat", "🐱"), ("dog", "🐶"), ("car", "🚗"), ("plane", "✈️"),
-        ("rocket", "🚀"), ("computer", "💻"), ("phone", "📱"), ("camera", "📷"), ("music", "🎵"),
-        ("game", "🎮"), ("food", "🍕"), ("coffee", "☕"), ("beer", "🍺"), ("fire", "🔥"),
-        ("star", "⭐"), ("money", "💰"), ("clock", "⏰"), ("email", "📧"), ("book", "📖"),
-    ];
+    #[test]
+    fn vdf_tokens_handles_comments_conditionals_and_escapes() {
+        let content = "\u{feff}// header comment\n\"Key\" \"C:\\\\Games\\\\\\\"Quoted\\\"\" [$WIN32]\nbare_key bare_value // trailing\n{ \"tab\\tbed\" }";
+        assert_eq!(
+            vdf_tokens(content),
+            vec![
+                VdfToken::Text("Key".to_string()),
+                VdfToken::Text("C:\\Games\\\"Quoted\"".to_string()),
+                VdfToken::Text("bare_key".to_string()),
+                VdfToken::Text("bare_value".to_string()),
+                VdfToken::Open,
+                VdfToken::Text("tab\tbed".to_string()),
+                VdfToken::Close,
+            ]
+        );
+    }
     
-    let alias_results: Vec<(String, String)> = common_aliases
-        .iter()
-        .filter(|(alias, _)| alias.contains(&query_lower))
-        .map(|(alias, emoji)| (alias.to_string(), emoji.to_string()))
-        .take(3)
-        .collect();
+    #[test]
+    fn parse_vdf_builds_nested_objects() {
+        let entries = parse_vdf("\"root\" { \"name\" \"value\" \"child\" { \"inner\" \"1\" } \"after\" \"2\" }");
+        assert_eq!(
+            entries,
+            vec![(
+                "root".to_string(),
+                VdfValue::Object(vec![
+                    ("name".to_string(), VdfValue::Text("value".to_string())),
+                    ("child".to_string(), VdfValue::Object(vec![("inner".to_string(), VdfValue::Text("1".to_string()))])),
+                    ("after".to_string(), VdfValue::Text("2".to_string())),
+                ])
+            )]
+        );
+        let Some(VdfValue::Object(root)) = vdf_get(&entries, "ROOT") else {
+            panic!("root object missing");
+        };
+        assert_eq!(vdf_text(root, "Name"), Some("value"));
+        assert_eq!(vdf_text(root, "child"), None);
+        
+        for content in ["", "{", "}", "\"a\"", "\"a\" {", "\"a\" { \"b\" { }", "\"unterminated", "\"a\" \"b\\"] {
+            let _ = parse_vdf(content);
+        }
+    }
     
-    let crate_results: Vec<(String, String)> = emojis::iter()
-        .filter_map(|emoji| {
-            if emoji.name().to_lowercase().contains(&query_lower) {
-                Some((emoji.name().to_string(), emoji.as_str().to_string()))
-            } else {
-                None
-            }
-        })
-        .take(2)
-        .collect();
+    #[test]
+    fn steam_library_paths_reads_nested_and_legacy_formats() {
+        assert_eq!(
+            steam_library_paths(include_str!("../tests/fixtures/libraryfolders.vdf")),
+            vec![PathBuf::from("C:\\Program Files (x86)\\Steam"), PathBuf::from("D:\\SteamLibrary")]
+        );
+        assert_eq!(
+            steam_library_paths(include_str!("../tests/fixtures/libraryfolders_legacy.vdf")),
+            vec![PathBuf::from("D:\\SteamLibrary"), PathBuf::from("E:\\Games\\Steam")]
+        );
+        assert!(steam_library_paths("\"AppState\" { \"appid\" \"570\" }").is_empty());
+    }
     
-    let mut combined = alias_results;
-    for result in crate_results {
-        if !combined.iter().any(|(_, emoji)| emoji == &result.1) {
-            combined.push(result);
+    #[test]
+    fn parse_steam_manifest_keeps_installed_games_only() {
+        assert_eq!(
+            parse_steam_manifest(include_str!("../tests/fixtures/appmanifest_570.acf")),
+            Some(SteamGame { app_id: 570, name: "Dota 2".to_string() })
+        );
+        assert_eq!(
+            parse_steam_manifest(include_str!("../tests/fixtures/appmanifest_1245620.acf")),
+            Some(SteamGame { app_id: 1245620, name: "ELDEN RING".to_string() })
+        );
+        assert_eq!(parse_steam_manifest(include_str!("../tests/fixtures/appmanifest_730.acf")), None);
+        assert_eq!(parse_steam_manifest(include_str!("../tests/fixtures/appmanifest_1493710.acf")), None);
+        assert_eq!(parse_steam_manifest(include_str!("../tests/fixtures/appmanifest_228980.acf")), None);
+        assert_eq!(
+            parse_steam_manifest("\"AppState\" { \"appid\" \"1628350\" \"name\" \"Steam Linux Runtime 3.0 (sniper)\" \"StateFlags\" \"4\" }"),
+            None
+        );
+        assert_eq!(parse_steam_manifest("\"AppState\" { \"appid\" \"abc\" \"name\" \"Game\" \"StateFlags\" \"4\" }"), None);
+        assert_eq!(parse_steam_manifest("\"AppState\" { \"appid\" \"10\" \"name\" \" \" \"StateFlags\" \"4\" }"), None);
+    }
+    
+    #[test]
+    fn shortcut_exec_keeps_windows_quoted_arguments() {
+        let link = parse_shell_link(include_bytes!("../tests/fixtures/quoted_arguments.lnk")).unwrap();
+        assert_eq!(link.target, "C:\\Windows\\notepad.exe");
+        assert_eq!(
+            exec_argv(&test_app("Notepad"), &shortcut_exec(&link.target, &link.arguments)),
+            vec![
+                "C:\\Windows\\notepad.exe",
+                "\\\\server\\share\\f.txt",
+                "C:\\dir\\",
+                "C:\\plain\\path",
+                "--name=a b",
+                "",
+                "say \"hi\"",
+                "100%",
+            ]
+        );
+    }
+    
+    #[test]
+    fn split_windows_args_follows_command_line_rules() {
+        assert_eq!(split_windows_args(""), Vec::<String>::new());
+        assert_eq!(split_windows_args("  a\tb  "), vec!["a", "b"]);
+        assert_eq!(split_windows_args("a\\\\b \\\\\\\\server"), vec!["a\\\\b", "\\\\\\\\server"]);
+        assert_eq!(split_windows_args("\"C:\\dir\\\" next"), vec!["C:\\dir\" next"]);
+        assert_eq!(split_windows_args("a\\\\\\\"b \"c\"\"d\""), vec!["a\\\"b", "c\"d"]);
+        assert_eq!(split_windows_args("x\"y z\"w"), vec!["xy zw"]);
+    }
+    
+    #[test]
+    fn join_windows_args_round_trips_through_split() {
+        let cases: [&[&str]; 6] = [
+            &[],
+            &["plain", "C:\\dir\\file.txt"],
+            &["with space", "", "tab\there"],
+            &["C:\\dir\\", "C:\\dir with space\\"],
+            &["say \"hi\"", "\\\"", "\\\\server\\share"],
+            &["a&b", "x|y", "^caret", "100%"],
+        ];
+        for args in cases {
+            let args: Vec<String> = args.iter().map(|arg| arg.to_string()).collect();
+            assert_eq!(split_windows_args(&join_windows_args(&args)), args);
         }
+        assert_eq!(join_windows_args(&["C:\\dir with space\\".to_string()]), "\"C:\\dir with space\\\\\"");
     }
     
-    combined.truncate(5);
-    combined
-}
-
-fn looks_like_url(text: &str) -> bool {
-    let text = text.trim();
+    #[test]
+    fn tokenize_exec_handles_quotes_and_escapes() {
+        let cases: [(&str, &[&str]); 9] = [
+            ("app --flag", &["app", "--flag"]),
+            ("  app   --flag  ", &["app", "--flag"]),
+            ("\"/opt/My App/app\" \"two words\"", &["/opt/My App/app", "two words"]),
+            ("app \"\" last", &["app", "", "last"]),
+            ("app a\"b c\"d", &["app", "ab cd"]),
+            ("sh -c \"echo \\\"hi\\\" \\`date\\` \\$HOME \\\\\"", &["sh", "-c", "echo \"hi\" `date` $HOME \\"]),
+            ("app \"C:\\\\dir\\\\\"", &["app", "C:\\dir\\"]),
+            ("app a\\b \"c\\d\"", &["app", "a\\b", "c\\d"]),
+            ("", &[]),
+        ];
+        for (exec, expected) in cases {
+            assert_eq!(tokenize_exec(exec), expected, "{}", exec);
+        }
+    }
     
-    if text.contains("://") {
-        return text.starts_with("http://") || text.starts_with("https://");
+    #[cfg(not(target_os = "windows"))]
+    #[test]
+    fn exec_values_are_unescaped_twice() {
+        let exec = unescape_desktop_value("sh -c \"printf \\\\\\\\n\" \"say \\\\\"hi\\\\\"\" x\\sy");
+        assert_eq!(exec, "sh -c \"printf \\\\n\" \"say \\\"hi\\\"\" x y");
+        assert_eq!(tokenize_exec(&exec), vec!["sh", "-c", "printf \\n", "say \"hi\"", "x", "y"]);
+        assert_eq!(unescape_desktop_value("tab\\there\\"), "tab\there\\");
+        assert_eq!(unescape_desktop_value("keep\\q"), "keep\\q");
     }
     
-    if text.contains('.') && !text.contains(' ') {
-        let domain_part = if text.contains('/') {
-            text.split('/').next().unwrap_or("")
-        } else {
-            text
-        };
+    #[test]
+    fn exec_argv_expands_field_codes() {
+        let mut app = test_app("Firefox Web");
+        let cases: [(&str, &[&str]); 8] = [
+            ("app %% 100%%", &["app", "%", "100%"]),
+            ("app %f %F %u %U %d %D %n %N %v %m --new", &["app", "--new"]),
+            ("app --file=%f --url=%u", &["app", "--file=", "--url="]),
+            ("app %i", &["app"]),
+            ("app --class=%c \"%c\"", &["app", "--class=Firefox Web", "Firefox Web"]),
+            ("app %k", &["app", ""]),
+            ("app \"%%f\"", &["app", "%f"]),
+            ("app %x", &["app", ""]),
+        ];
+        for (exec, expected) in cases {
+            assert_eq!(exec_argv(&app, exec), expected, "{}", exec);
+        }
         
-        let parts: Vec<&str> = domain_part.split('.').collect();
-        if parts.len() >= 2 {
-            let last_part = parts.last().unwrap();
-            
-            let common_tlds = [
-                "com", "org", "net", "io", "co", "me", "dev", "app", "tech", "xyz",
-                "us", "uk", "ca", "au", "de", "fr", "jp", "in", "br", "ru",
-            ];
-            
-            return common_tlds.iter().any(|&tld| *last_part == tld) || 
-                   last_part.len() == 2;
+        app.icon = "firefox".to_string();
+        app.desktop_file = Some(PathBuf::from("/usr/share/applications/firefox.desktop"));
+        assert_eq!(exec_argv(&app, "app %i -- %U"), vec!["app", "--icon", "firefox", "--"]);
+        assert_eq!(exec_argv(&app, "app %k"), vec!["app", "/usr/share/applications/firefox.desktop"]);
+    }
+    
+    #[test]
+    fn dedup_launch_commands_keeps_distinct_targets_with_the_same_name() {
+        let app = |name: &str, exec: &str| AppEntry { exec_command: exec.to_string(), ..test_app(name) };
+        let mut apps = vec![
+            app("Firefox", "\"C:\\\\Program Files\\\\Mozilla Firefox\\\\firefox.exe\""),
+            app("Firefox", "\"C:\\\\Program Files\\\\Mozilla Firefox\\\\firefox.exe\" -private-window"),
+            app("Setup", "\"C:\\\\Program Files\\\\Tool A\\\\setup.exe\""),
+            app("Setup", "\"C:\\\\Program Files\\\\Tool B\\\\setup.exe\""),
+            app("Firefox (all users)", "\"c:\\\\program files\\\\mozilla firefox\\\\FIREFOX.EXE\""),
+            app("Notepad", "\"C:\\\\Windows\\\\notepad.exe\""),
+            app("Notepad", "C:\\Windows\\notepad.exe"),
+        ];
+        dedup_launch_commands(&mut apps);
+        let names: Vec<&str> = apps.iter().map(|app| app.name.as_str()).collect();
+        assert_eq!(names, vec!["Firefox", "Firefox", "Setup", "Setup", "Notepad"]);
+    }
+    
+    #[test]
+    fn decode_icon_file_reads_ico_images() {
+        let image = decode_icon_file(include_bytes!("../tests/fixtures/site.ico")).unwrap();
+        assert_eq!(image.size, [16, 16]);
+        assert_eq!(image.pixels[0], egui::Color32::from_rgb(255, 0, 0));
+        assert_eq!(image.pixels[16 * 16 - 1], egui::Color32::from_rgb(0, 0, 255));
+        assert!(decode_icon_file(include_bytes!("../tests/fixtures/not_pe.exe")).is_err());
+        assert!(decode_icon_file(&[]).is_err());
+    }
+    
+    fn result_labels(results: &[ResultType]) -> Vec<String> {
+        results.iter()
+            .map(|result| match result {
+                ResultType::App(app) => app.name.clone(),
+                ResultType::AppAction(app, action) => format!("{} › {}", app.name, action.name),
+                ResultType::Calculator(value) => format!("= {}", value),
+                ResultType::Url(url) => url.clone(),
+                ResultType::WebSearch(query) => format!("web: {}", query),
+                ResultType::Suggestion(suggestion) => format!("suggest: {}", result_labels(std::slice::from_ref(suggestion.as_ref()))[0]),
+                _ => result.provider().to_string(),
+            })
+            .collect()
+    }
+    
+    fn set_provider(config: &mut SearchConfig, name: &str, weight: i64, max_results: usize) {
+        if let Some((_, settings)) = config.providers.iter_mut().find(|(provider, _)| provider == name) {
+            *settings = ProviderSettings { weight, max_results };
         }
     }
     
-    false
-}
-
-fn acquire_lock() -> Result<File, String> {
-    let lock_path = get_lock_path();
+    fn scored_apps(apps: &[(f64, &str)]) -> Vec<(f64, ResultType)> {
+        apps.iter().map(|(relevance, name)| (*relevance, ResultType::App(test_app(name)))).collect()
+    }
+    
+    #[test]
+    fn blend_results_orders_providers_by_weighted_relevance() {
+        let mut config = SearchConfig::default();
+        let web = || ("web", vec![(0.05, ResultType::WebSearch("fire".to_string()))]);
+        let providers = || vec![
+            ("calculator", vec![(1.0, ResultType::Calculator("4".to_string()))]),
+            ("url", vec![(0.7, ResultType::Url("https://fire".to_string()))]),
+            ("apps", scored_apps(&[(0.6, "Firewall"), (0.9, "Firefox")])),
+            web(),
+        ];
+        let none = std::collections::HashMap::new();
+        assert_eq!(
+            result_labels(&blend_results(&config, &none, providers(), 8)),
+            vec!["= 4", "Firefox", "https://fire", "Firewall", "web: fire"]
+        );
+        
+        set_provider(&mut config, "apps", 50, 8);
+        set_provider(&mut config, "calculator", 40, 1);
+        assert_eq!(
+            result_labels(&blend_results(&config, &none, providers(), 8)),
+            vec!["https://fire", "Firefox", "= 4", "Firewall", "web: fire"]
+        );
+        
+        let boosts = std::collections::HashMap::from([("app:firewall.desktop", 0.5)]);
+        assert_eq!(
+            result_labels(&blend_results(&config, &boosts, providers(), 8)),
+            vec!["https://fire", "Firewall", "Firefox", "= 4", "web: fire"]
+        );
+    }
+    
+    #[test]
+    fn blend_results_caps_each_provider() {
+        let mut config = SearchConfig::default();
+        set_provider(&mut config, "apps", 100, 2);
+        let providers = vec![
+            ("apps", scored_apps(&[(0.3, "C"), (0.9, "A"), (0.1, "E"), (0.6, "B"), (0.2, "D")])),
+            ("web", vec![(0.05, ResultType::WebSearch("q".to_string()))]),
+        ];
+        assert_eq!(
+            result_labels(&blend_results(&config, &std::collections::HashMap::new(), providers, 8)),
+            vec!["A", "B", "web: q"]
+        );
+    }
     
-    if lock_path.exists() {
-        return Err("Flint is already running!".to_string());
+    #[test]
+    fn blend_results_keeps_the_web_fallback_visible() {
+        let config = SearchConfig::default();
+        let none = std::collections::HashMap::new();
+        let many: Vec<(f64, &str)> = (0..8).map(|i| (0.9 - i as f64 * 0.05, ["A", "B", "C", "D", "E", "F", "G", "H"][i])).collect();
+        let providers = vec![
+            ("apps", scored_apps(&many)),
+            ("web", vec![(0.05, ResultType::WebSearch("q".to_string()))]),
+        ];
+        assert_eq!(
+            result_labels(&blend_results(&config, &none, providers, 4)),
+            vec!["A", "B", "C", "web: q"]
+        );
+        
+        let providers = vec![
+            ("apps", Vec::new()),
+            ("suggestions", vec![(0.2, ResultType::Suggestion(Box::new(ResultType::App(test_app("Firefox")))))]),
+            ("web", vec![(0.05, ResultType::WebSearch("fierfox".to_string()))]),
+        ];
+        assert_eq!(
+            result_labels(&blend_results(&config, &none, providers, 4)),
+            vec!["suggest: Firefox", "web: fierfox"]
+        );
     }
     
-    let mut lock_file = OpenOptions::new()
-        .write(true)
-        .create(true)
-        .truncate(true)
-        .open(&lock_path)
-        .map_err(|e| format!("Failed to create lock file: {}", e))?;
+    #[test]
+    fn blend_results_lists_actions_under_the_top_app() {
+        let mut app = test_app("Firefox");
+        app.actions = vec![DesktopAction { name: "New Window".to_string(), exec: "firefox --new-window".to_string() }];
+        let providers = vec![
+            ("apps", vec![(0.9, ResultType::App(app)), (0.5, ResultType::App(test_app("Files")))]),
+        ];
+        assert_eq!(
+            result_labels(&blend_results(&SearchConfig::default(), &std::collections::HashMap::new(), providers, 8)),
+            vec!["Firefox", "Firefox › New Window", "Files"]
+        );
+    }
     
-    let pid = std::process::id();
-    lock_file.write_all(pid.to_string().as_bytes())
-        .map_err(|e| format!("Failed to write PID: {}", e))?;
+    #[test]
+    fn typo_suggestions_appear_only_without_fuzzy_matches() {
+        let apps = vec![test_app("Firefox"), test_app("Files"), test_app("Terminal")];
+        let config = SearchConfig::default();
+        let index = build_app_index(&apps, config.transliterate);
+        let matcher = config.backend.matcher();
+        let learned = std::collections::HashSet::new();
+        let fuzzy = |query: &str| -> Vec<(f64, ResultType)> {
+            match_apps(&apps, &index, &AppQuery::new(query, config.transliterate), matcher.as_ref(), &config, &learned, None)
+                .results
+                .into_iter()
+                .map(|(score, result)| (score as f64 / APP_SCORE_SCALE, result))
+                .collect()
+        };
+        let suggest = |query: &str| {
+            let matched = fuzzy(query);
+            suggestion_results(&[&matched], typo_suggestions(&apps, &index, &[], &fold_text(query, config.transliterate)))
+        };
+        
+        assert!(fuzzy("fierfox").is_empty());
+        assert_eq!(result_labels(&typo_suggestions(&apps, &index, &[], "fierfox")), vec!["Firefox"]);
+        assert_eq!(
+            result_labels(&suggest("fierfox").into_iter().map(|(_, result)| result).collect::<Vec<_>>()),
+            vec!["suggest: Firefox"]
+        );
+        assert_eq!(result_labels(&typo_suggestions(&apps, &index, &[], "termnial")), vec!["Terminal"]);
+        
+        assert!(!typo_suggestions(&apps, &index, &[], "firefo").is_empty());
+        assert!(suggest("firefo").is_empty());
+        assert!(typo_suggestions(&apps, &index, &[], "fi").is_empty());
+        assert!(typo_suggestions(&apps, &index, &[], "zzzzzz").is_empty());
+    }
     
-    Ok(lock_file)
-}
-
-fn get_lock_path() -> PathBuf {
-    std::env::temp_dir().join("flint.lock")
-}
-
-#[cfg(target_os = "windows")]
-fn launch_app(exec_command: &str) {
-    let _ = Command::new("cmd")
-        .args(&["/C", "start", "", exec_command])
-        .spawn();
-}
-
-#[cfg(not(target_os = "windows"))]
-fn launch_app(exec_command: &str) {
-    let _ = Command::new("sh")
-        .arg("-c")
-        .arg(exec_command)
-        .spawn();
-}
-
-#[cfg(target_os = "windows")]
-fn scan_apps() -> Vec<AppEntry> {
-    scan_windows_apps()
-}
-
-#[cfg(not(target_os = "windows"))]
-fn scan_apps() -> Vec<AppEntry> {
-    scan_linux_apps()
-}
-
-fn scan_windows_apps() -> Vec<AppEntry> {
-    let mut apps = Vec::new();
-
-    let common_apps = [
-        ("Notepad", "notepad.exe"),
-        ("Calculator", "calc.exe"), 
-        ("Paint", "mspaint.exe"),
-        ("Command Prompt", "cmd.exe"),
-        ("PowerShell", "powershell.exe"),
-        ("File Explorer", "explorer.exe"),
-        ("Task Manager", "taskmgr.exe"),
-        ("Control Panel", "control.exe"),
-        ("System Configuration", "msconfig.exe"),
-        ("Registry Editor", "regedit.exe"),
-        ("Windows Media Player", "wmplayer.exe"),
-        ("WordPad", "write.exe"),
-        ("Snipping Tool", "snippingtool.exe"),
-        ("Sticky Notes", "stikynot.exe"),
-    ];
-
-    for (name, exec) in common_apps {
-        apps.push(AppEntry {
-            name: name.to_string(),
-            desktop_id: name.to_string(),
-            exec_command: exec.to_string(),
-            match_indices: Vec::new(),
-        });
+    #[test]
+    fn typo_suggestions_rank_above_the_web_fallback() {
+        let apps = vec![test_app("Firefox"), test_app("Files")];
+        let index = build_app_index(&apps, true);
+        let quicklinks = vec![Quicklink {
+            keyword: "jira".to_string(),
+            kind: QuicklinkKind::Url,
+            target: "https://jira.example.com/browse/{}".to_string(),
+        }];
+        let suggestions = suggestion_results(&[&[]], typo_suggestions(&apps, &index, &quicklinks, "fierfox"));
+        let providers = vec![
+            ("web", vec![(0.05, ResultType::WebSearch("fierfox".to_string()))]),
+            ("suggestions", suggestions),
+        ];
+        assert_eq!(
+            result_labels(&blend_results(&SearchConfig::default(), &std::collections::HashMap::new(), providers, 8)),
+            vec!["suggest: Firefox", "web: fierfox"]
+        );
+        
+        let suggestions = suggestion_results(&[&[]], typo_suggestions(&apps, &index, &quicklinks, "jria abc-1"));
+        assert!(matches!(suggestions.as_slice(), [(_, ResultType::Suggestion(link))] if matches!(link.as_ref(), ResultType::Quicklink(link, _) if link.keyword == "jira")));
     }
-
-    let program_dirs = [
-        std::env::var("PROGRAMFILES").unwrap_or_else(|_| "C:\\Program Files".to_string()),
-        std::env::var("PROGRAMFILES(X86)").unwrap_or_else(|_| "C:\\Program Files (x86)".to_string()),
-        std::env::var("LOCALAPPDATA").unwrap_or_else(|_| "C:\\Users\\Default\\AppData\\Local".to_string()),
-    ];
-
-    for program_dir in &program_dirs {
-        let program_path = PathBuf::from(program_dir);
-        if program_path.exists() {
-            if let Ok(entries) = fs::read_dir(&program_path) {
-                for entry in entries.flatten() {
-                    let path = entry.path();
-                    if path.is_dir() {
-                        if let Some(folder_name) = path.file_name().and_then(|s| s.to_str()) {
-                            if let Ok(sub_entries) = fs::read_dir(&path) {
-                                for sub_entry in sub_entries.flatten() {
-                                    let sub_path = sub_entry.path();
-                                    if sub_path.extension().and_then(|e| e.to_str()) == Some("exe") {
-                                        if let Some(exe_name) = sub_path.file_stem().and_then(|s| s.to_str()) {
-                                            apps.push(AppEntry {
-                                                name: format!("{} - {}", folder_name, exe_name),
-                                                desktop_id: folder_name.to_string(),
-                                                exec_command: sub_path.to_string_lossy().to_string(),
-                                                match_indices: Vec::new(),
-                                            });
-                                        }
-                                    }
-                                }
-                            }
-                            
-                            apps.push(AppEntry {
-                                name: folder_name.to_string(),
-                                desktop_id: folder_name.to_string(),
-                                exec_command: format!("explorer \"{}\"", path.display()),
-                                match_indices: Vec::new(),
-                            });
-                        }
-                    }
-                }
-            }
-        }
+    
+    #[test]
+    fn parse_currency_query_recognises_conversions_only() {
+        assert_eq!(parse_currency_query("10 usd eur"), Some((10.0, "USD".to_string(), "EUR".to_string())));
+        assert_eq!(parse_currency_query("2.5 euros to yen"), Some((2.5, "EUR".to_string(), "JPY".to_string())));
+        assert_eq!(parse_currency_query("convert 3 gbp to usd"), Some((3.0, "GBP".to_string(), "USD".to_string())));
+        assert_eq!(parse_currency_query("firefox"), None);
+        assert_eq!(parse_currency_query("10 usd"), None);
+        assert_eq!(parse_currency_query("ten usd to eur"), None);
+        assert_eq!(parse_currency_query("10 dollars to somewhere"), None);
     }
-
-    apps.sort_by(|a, b| a.name.cmp(&b.name));
-    apps.dedup_by(|a, b| a.name == b.name);
-    apps
-}
-
-fn scan_linux_apps() -> Vec<AppEntry> {
-    let mut apps = Vec::new();
     
-    let common_apps = [
-        ("Firefox", "firefox"),
-        ("Terminal", "gnome-terminal"),
-        ("Files", "nautilus"),
-        ("Text Editor", "gedit"),
-        ("Calculator", "gnome-calculator"),
-        ("Settings", "gnome-control-center"),
-    ];
+    fn app_score(app: &AppEntry, query: &str, config: &SearchConfig) -> Option<(i64, Vec<usize>)> {
+        let search = AppSearchText::new(app, config.transliterate);
+        score_app(&search, &AppQuery::new(query, config.transliterate), config.backend.matcher().as_ref(), config)
+    }
     
-    for (name, exec) in common_apps {
-        apps.push(AppEntry {
-            name: name.to_string(),
-            desktop_id: name.to_string(),
-            exec_command: exec.to_string(),
-            match_indices: Vec::new(),
-        });
+    fn score_of(app: &AppEntry, query: &str) -> i64 {
+        app_score(app, query, &SearchConfig::default()).map_or(0, |(score, _)| score)
     }
     
-    let desktop_dirs = [
-        dirs::data_dir().map(|p| p.join("applications")),
-        Some(PathBuf::from("/usr/share/applications")),
-        Some(PathBuf::from("/usr/local/share/applications")),
-    ];
+    #[test]
+    fn score_app_matches_acronyms() {
+        let config = SearchConfig::default();
+        let code = test_app("Visual Studio Code");
+        assert_eq!(app_score(&code, "vsc", &config).map(|(_, indices)| indices), Some(vec![0, 7, 14]));
+        assert!(score_of(&code, "vsc") > score_of(&test_app("Devices Control"), "vsc"));
+        assert_eq!(acronym_indices(&AppSearchText::new(&test_app("GNU Image Manipulation Program"), true).initials, &['i', 'm']), Some((false, vec![4, 10])));
+        assert_eq!(acronym_indices(&AppSearchText::new(&code, true).initials, &['v']), None);
+    }
     
-    for dir_option in &desktop_dirs {
-        if let Some(dir) = dir_option {
-            if dir.exists() {
-                if let Ok(entries) = fs::read_dir(dir) {
-                    for entry in entries.flatten() {
-                        let path = entry.path();
-                        if path.extension().and_then(|e| e.to_str()) == Some("desktop") {
-                            if let Some(name) = path.file_stem().and_then(|s| s.to_str()) {
-                                apps.push(AppEntry {
-                                    name: name.to_string(),
-                                    desktop_id: name.to_string(),
-                                    exec_command: path.to_string_lossy().to_string(),
-                                    match_indices: Vec::new(),
-                                });
-                            }
-                        }
-                    }
-                }
-            }
-        }
+    #[test]
+    fn score_app_prefers_prefix_and_word_boundary_matches() {
+        assert!(score_of(&test_app("Terminal"), "term") > score_of(&test_app("Xterm"), "term"));
+        assert!(score_of(&test_app("Code Editor"), "code") > score_of(&test_app("Barcode Scanner"), "code"));
+        assert!(score_of(&test_app("Disk Usage"), "du") > score_of(&test_app("Adobe Audition"), "du"));
+        assert_eq!(match_bonus("firefox", "fire", &[0, 1, 2, 3]), WORD_BOUNDARY_BONUS + PREFIX_BONUS);
+        assert_eq!(match_bonus("web browser", "br", &[4, 5]), WORD_BOUNDARY_BONUS);
+        assert_eq!(match_bonus("zebra", "br", &[2, 3]), 0);
     }
     
-    apps.sort_by(|a, b| a.name.cmp(&b.name));
-    apps.dedup_by(|a, b| a.name == b.name);
-    apps
-}
-
-#[cfg(target_os = "windows")]
-fn get_config_dir() -> PathBuf {
-    dirs::config_dir()
-        .map(|p| p.join("Flint"))
-        .unwrap_or_else(|| PathBuf::from("C:\\ProgramData\\Flint"))
-}
-
-#[cfg(not(target_os = "windows"))]
-fn get_config_dir() -> PathBuf {
-    dirs::config_dir()
-        .map(|p| p.join("flint"))
-        .unwrap_or_else(|| PathBuf::from("~/.config/flint"))
-}
-
-fn create_default_theme(theme_path: &PathBuf) {
-    let default_theme = r#"# Flint Theme Configuration
-# Dark Theme
-
-# Main window colors
-background=#2d2d30
-text_color=#ffffff
-selection_bg=#0078d4
-selection_text=#ffffff
-border_color=#3e3e42
-highlight_color=#0078d4
-
-# Font settings
-font_size=16
-font_family=Segoe UI
-
-# Border radius
-border_radius=2
-"#;
+    #[test]
+    fn score_app_matches_generic_names_and_keywords() {
+        let firefox = AppEntry { generic_name: "Web Browser".to_string(), ..test_app("Firefox") };
+        let chromium = AppEntry { keywords: vec!["Internet".to_string(), "WWW".to_string(), "Browser".to_string()], ..test_app("Chromium") };
+        assert!(score_of(&firefox, "browser") > 0);
+        assert!(score_of(&chromium, "browser") > 0);
+        assert_eq!(app_score(&test_app("Calculator"), "browser", &SearchConfig::default()), None);
+        
+        let matcher = SearchConfig::default().backend.matcher();
+        assert!(word_prefix_score("internet www browser", "brow", matcher.as_ref()).is_some());
+        assert_eq!(word_prefix_score("eyebrows", "brow", matcher.as_ref()), None);
+    }
     
-    if let Some(parent) = theme_path.parent() {
-        let _ = fs::create_dir_all(parent);
+    #[test]
+    fn score_app_weights_fields() {
+        let notes = test_app("Notes");
+        let editor = AppEntry { keywords: vec!["notes".to_string()], ..test_app("Editor") };
+        let described = AppEntry { comment: "Take notes".to_string(), ..test_app("Jotter") };
+        assert!(score_of(&notes, "notes") > score_of(&editor, "notes"));
+        assert!(score_of(&editor, "notes") > score_of(&described, "notes"));
+        
+        let mut config = SearchConfig { keywords_weight: 0, ..SearchConfig::default() };
+        assert_eq!(app_score(&editor, "notes", &config), None);
+        config.keywords_weight = 300;
+        assert!(app_score(&editor, "notes", &config).unwrap().0 > app_score(&described, "notes", &config).unwrap().0);
+        config.name_weight = 0;
+        config.exec_weight = 0;
+        assert_eq!(app_score(&notes, "notes", &config), None);
     }
-    let _ = fs::write(theme_path, default_theme);
-}
-
-fn main() -> eframe::Result<()> {
-    let args: Vec<String> = std::env::args().collect();
-    let run_in_tray = args.len() > 1 && args[1] == "--tray";
     
-    if run_in_tray {
-        println!("Flint Launcher running in system tray...");
-        println!("Config location: {}", get_config_dir().display());
-        println!("Right-click the tray icon to access options.");
+    #[test]
+    fn fold_text_removes_accents_and_case() {
+        assert_eq!(fold_text("Café", false), "cafe");
+        assert_eq!(fold_text("ÉCOLE Çà", false), "ecole ca");
+        assert_eq!(fold_text("Cafe\u{301}", false), "cafe");
+        assert_eq!(fold_text("ﬁle", false), "file");
+        assert_eq!(fold_text("微信", false), "微信");
+    }
+    
+    #[test]
+    fn fold_text_transliterates_non_latin_scripts() {
+        assert_eq!(fold_text("Москва", true), "moskva");
+        assert_eq!(fold_text("微信", true), "weixin");
+        assert_eq!(fold_text("Straße", true), "strasse");
+        assert_eq!(fold_text("Ελληνικά", true), "ellinika");
+    }
+    
+    #[test]
+    fn normalized_text_maps_indices_to_original_characters() {
+        let text = NormalizedText::new("Straße", true);
+        assert_eq!(text.text, "strasse");
+        assert_eq!(text.origin, vec![0, 1, 2, 3, 4, 4, 5]);
+        assert_eq!(text.original_indices(&[4, 5, 6]), vec![4, 5]);
         
-        start_tray_thread();
+        let text = NormalizedText::new("微信 Chat", true);
+        assert_eq!(text.text, "weixin chat");
+        assert_eq!(text.original_indices(&[0, 1, 2]), vec![0]);
+        assert_eq!(text.original_indices(&[3, 5, 7]), vec![1, 3]);
+        assert_eq!(text.original_indices(&[99]), Vec::<usize>::new());
         
-        loop {
-            thread::sleep(Duration::from_secs(10));
-        }
+        let text = NormalizedText::new("Cafe\u{301} Bar", false);
+        assert_eq!(text.text, "cafe bar");
+        assert_eq!(text.original_indices(&[3, 5]), vec![3, 6]);
     }
     
-    let mode = if args.len() > 1 && args[1] == "settings" {
-        AppMode::Settings
-    } else {
-        AppMode::Launcher
-    };
-
-    let app = match FlintApp::new() {
-        Ok(mut app) => {
-            app.app_mode = mode;
-            app
-        }
-        Err(e) => {
-            eprintln!("{}", e);
-            std::process::exit(1);
-        }
-    };
-    
-    let (title, width, height) = if mode == AppMode::Settings {
-        ("Flint Settings", 550.0, 600.0)
-    } else {
-        ("Flint", 600.0, 50.0)
-    };
-
-    let options = eframe::NativeOptions {
-        viewport: egui::ViewportBuilder::default()
-            .with_inner_size([width, height])
-            .with_decorations(mode == AppMode::Settings)
-            .with_always_on_top()
-            .with_resizable(false)
-            .with_window_level(egui::WindowLevel::AlwaysOnTop)
-            .with_position(egui::pos2(
-                (1920.0 - width) / 2.0,
-                200.0,
-            )),
-        centered: false,
-        ..Default::default()
-    };
-
-    eframe::run_native(
-        title,
-        options,
-        Box::new(move |cc| {
-            cc.egui_ctx.send_viewport_cmd(egui::ViewportCommand::Focus);
-            Box::new(app)
-        }),
-    )
-}
\ No newline at end of file
+    #[test]
+    fn score_app_highlights_original_characters() {
+        let config = SearchConfig::default();
+        let highlight = |name: &str, query: &str| app_score(&test_app(name), query, &config).map(|(_, indices)| indices);
+        assert_eq!(highlight("Café", "cafe"), Some(vec![0, 1, 2, 3]));
+        assert_eq!(highlight("微信", "weixin"), Some(vec![0, 1]));
+        assert_eq!(highlight("Straße Maps", "strasse"), Some(vec![0, 1, 2, 3, 4, 5]));
+        assert_eq!(highlight("Москва", "moskva"), Some(vec![0, 1, 2, 3, 4, 5]));
+    }
+}